@@ -0,0 +1,31 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+criterion_group!(benches, bench_days);
+criterion_main!(benches);
+
+/// Benchmarks parse, part 1 and part 2 of every day with an input in `inputs/`.
+fn bench_days(c: &mut Criterion) {
+    for day in aoc2021::DAYS {
+        let path = day.input_path();
+        let input = match std::fs::read_to_string(&path) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("skipping {}: {}: {}", day.name(), path.display(), err);
+                continue;
+            }
+        };
+        let parsed = match day.parse(&input) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                eprintln!("skipping {}: failed to parse: {}", day.name(), err);
+                continue;
+            }
+        };
+
+        let mut group = c.benchmark_group(format!("aoc2021::{}", day.name()));
+        group.bench_function("parse", |b| b.iter(|| black_box(day.parse(&input))));
+        group.bench_function("part1", |b| b.iter(|| black_box(parsed.part1())));
+        group.bench_function("part2", |b| b.iter(|| black_box(parsed.part2())));
+        group.finish();
+    }
+}
@@ -0,0 +1,19 @@
+//! Shared by the fuzz targets, one per day.
+
+/// Solves both parts of `day` with `data` as the input, reading it as a stream too if the day can.
+///
+/// Any input must either be rejected with an error or solved, panicking or running
+/// out of memory is a bug found by the fuzzer.
+pub fn solve(day: u8, data: &[u8]) {
+    let day = aoc2021::get_day(day).expect("solved day");
+    if let Ok(input) = std::str::from_utf8(data) {
+        if let Ok(parsed) = day.parse(input) {
+            let _ = parsed.part1();
+            let _ = parsed.part2();
+        }
+    }
+    if day.can_stream() {
+        let _ = day.stream_part1(&mut &*data);
+        let _ = day.stream_part2(&mut &*data);
+    }
+}
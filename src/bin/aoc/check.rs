@@ -0,0 +1,223 @@
+use std::{collections::BTreeMap, fmt::Write, path::Path};
+
+use serde::Deserialize;
+
+use aoc2021::{Answer, Day, Result};
+
+use crate::{
+    cli::{Part, RunOptions},
+    color::{paint, Color},
+    run::{read_input, select_days},
+};
+
+/// Expected answer, written as an integer or a string in `answers.toml`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Expected {
+    Number(i64),
+    Text(String),
+}
+
+impl std::fmt::Display for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expected::Number(n) => write!(f, "{n}"),
+            Expected::Text(s) => f.write_str(s.trim_end()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DayAnswers {
+    part1: Option<Expected>,
+    part2: Option<Expected>,
+}
+
+/// Known answers for the inputs in `inputs/`, keyed by day name like `day01`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Answers(BTreeMap<String, DayAnswers>);
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self> {
+        let s = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        Ok(toml::from_str(&s)?)
+    }
+
+    pub fn get(&self, day: &Day, part: Part) -> Option<&Expected> {
+        let answers = self.0.get(&day.name())?;
+        match part {
+            Part::One => answers.part1.as_ref(),
+            Part::Two => answers.part2.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Status {
+    Pass,
+    Fail { expected: String, actual: String },
+    Missing(Answer),
+    Skip(String),
+    Error(String),
+}
+
+impl Status {
+    fn new(expected: Option<&Expected>, actual: Result<Answer>) -> Self {
+        match (expected, actual) {
+            (_, Err(err)) => Status::Error(err.to_string()),
+            (None, Ok(actual)) => Status::Missing(actual),
+            (Some(expected), Ok(actual)) => {
+                let expected = expected.to_string();
+                let actual = actual.to_string();
+                if expected == actual.trim_end() {
+                    Status::Pass
+                } else {
+                    Status::Fail { expected, actual }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Summary {
+    passed: usize,
+    failed: usize,
+    missing: usize,
+    skipped: usize,
+    errors: usize,
+}
+
+pub fn check(opts: &RunOptions) -> Result<()> {
+    let answers = Answers::load(&opts.answers)?;
+    let mut summary = Summary::default();
+    for day in select_days(&opts.days)? {
+        let parsed = read_input(day, opts.input.as_ref()).map(|input| day.parse(&input));
+        for part in [Part::One, Part::Two] {
+            if !opts.runs(part) {
+                continue;
+            }
+            let status = match &parsed {
+                Err(err) => Status::Skip(err.to_string()),
+                Ok(Err(err)) => Status::Error(err.to_string()),
+                Ok(Ok(parsed)) => {
+                    let actual = match part {
+                        Part::One => parsed.part1(),
+                        Part::Two => parsed.part2(),
+                    };
+                    Status::new(answers.get(day, part), actual)
+                }
+            };
+            report(day, part, &status, &mut summary);
+        }
+    }
+
+    println!(
+        "\n{} passed, {} failed, {} missing, {} skipped, {} errors",
+        summary.passed, summary.failed, summary.missing, summary.skipped, summary.errors
+    );
+    if summary.failed + summary.errors > 0 {
+        return Err("check failed".into());
+    }
+    Ok(())
+}
+
+fn report(day: &Day, part: Part, status: &Status, summary: &mut Summary) {
+    let label = format!("{} part {}", day.name(), part);
+    match status {
+        Status::Pass => {
+            summary.passed += 1;
+            println!("{} {}", paint("PASS   ", Color::Green), label);
+        }
+        Status::Fail { expected, actual } => {
+            summary.failed += 1;
+            println!("{} {}", paint("FAIL   ", Color::Red), label);
+            print!("{}", diff(expected, actual));
+        }
+        Status::Missing(actual) => {
+            summary.missing += 1;
+            println!(
+                "{} {}: got {}",
+                paint("MISSING", Color::Yellow),
+                label,
+                actual
+            );
+        }
+        Status::Skip(reason) => {
+            summary.skipped += 1;
+            println!("{} {}: {}", paint("SKIP   ", Color::Yellow), label, reason);
+        }
+        Status::Error(err) => {
+            summary.errors += 1;
+            println!("{} {}: {}", paint("ERROR  ", Color::Red), label, err);
+        }
+    }
+}
+
+/// Line diff of `expected` (`-`) and `actual` (`+`), indented for the report.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    let mut out = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => {
+                let _ = writeln!(out, "          {e}");
+            }
+            (e, a) => {
+                if let Some(e) = e {
+                    let _ = writeln!(out, "        {}", paint(&format!("- {e}"), Color::Red));
+                }
+                if let Some(a) = a {
+                    let _ = writeln!(out, "        {}", paint(&format!("+ {a}"), Color::Green));
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers() {
+        let answers: Answers = toml::from_str(indoc::indoc! {r#"
+            [day07]
+            part1 = 37
+            part2 = "168"
+
+            [day13]
+            part2 = '''
+            #####
+            #...#
+            '''
+        "#})
+        .unwrap();
+        let day07 = aoc2021::get_day(7).unwrap();
+        let day13 = aoc2021::get_day(13).unwrap();
+
+        let status = Status::new(answers.get(day07, Part::One), Ok(Answer::Number(37)));
+        assert!(matches!(status, Status::Pass));
+        let status = Status::new(answers.get(day07, Part::Two), Ok(Answer::Number(167)));
+        assert!(matches!(status, Status::Fail { .. }));
+        let status = Status::new(answers.get(day13, Part::One), Ok(Answer::Number(17)));
+        assert!(matches!(status, Status::Missing(_)));
+        let paper = Answer::Text("#####\n#...#".to_owned());
+        let status = Status::new(answers.get(day13, Part::Two), Ok(paper));
+        assert!(matches!(status, Status::Pass));
+    }
+
+    #[test]
+    fn test_diff() {
+        std::env::set_var("NO_COLOR", "1");
+        assert_eq!(diff("1", "2"), "        - 1\n        + 2\n");
+        assert_eq!(
+            diff("a\nb", "a\nc"),
+            "          a\n        - b\n        + c\n"
+        );
+    }
+}
@@ -0,0 +1,906 @@
+use std::{ops::RangeInclusive, path::PathBuf, time::Duration};
+
+use thiserror::Error;
+
+use aoc2021::image_export;
+
+use crate::report::Format;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+pub const USAGE: &str = "\
+USAGE:
+    aoc run <DAYS>... [--part <1|2>] [--input <PATH|->] [--check [--answers <PATH>]]
+                     [--format <text|json|csv|markdown>] [--picture] [--stream] [--window <N>]
+    aoc fetch <DAYS>... [--base-url <URL>] [--cache <DIR>] [--interval <SECS>] [--force]
+    aoc new <DAY>
+    aoc gen-input <DAY> [--seed <N>] [--size <M>]
+    aoc viz <DAY> [--input <PATH>] [--fps <N>] [--paused] [--width <COLS>] [--height <ROWS>]
+    aoc image <DAY> [--output <PATH>] [--format <ppm|svg|png>] [--scale <N>] [--input <PATH|->]
+    aoc depth-report [--input <PATH|->] [--window <N>] [--json]
+    aoc trajectory [--input <PATH|->] [--output <PATH>] [--format <csv|svg>]
+    aoc help
+
+DAYS:
+    5         a single day
+    1..=13    an inclusive range of days (`1..14` is exclusive)
+    all       every day with a solution
+
+OPTIONS:
+    -p, --part <1|2>         only run the given part
+    -i, --input <PATH|->     read input from PATH, or STDIN with `-`,
+                             instead of `inputs/dayNN.txt` (single day only)
+        --check              compare the answers with the known answers
+        --answers <PATH>     known answers for --check [default: answers.toml]
+    -f, --format <FORMAT>    output format of the answers and timings:
+                             text, json, csv or markdown [default: text]
+        --picture            also print the picture of answers read with OCR
+        --stream             solve reading the input one line at a time, for days
+                             that support it (1, 2, 5 and 10); the input is read
+                             once per part, so STDIN needs --part; memory stays
+                             the same except day 5, which keeps the points
+                             covered, and day 10 part 2, which keeps one score
+                             per incomplete line
+        --window <N>         day 1 only: part 2 compares sums of N measurements
+                             instead of 3
+
+FETCH OPTIONS:
+        --base-url <URL>     server to fetch inputs from [default: https://adventofcode.com]
+        --cache <DIR>        directory of the downloaded inputs [default: inputs]
+        --interval <SECS>    minimum time between requests [default: 1]
+        --force              download again even if the input is cached
+
+`aoc fetch` reads the session cookie from AOC_SESSION, or from `.env`.
+It needs the `fetch` feature, e.g., `cargo run --release --features fetch --bin aoc -- fetch 1`.
+
+GEN-INPUT OPTIONS:
+        --seed <N>           seed of the random input [default: 0]
+        --size <M>           size of the input, e.g., the number of lines for day 1
+                             or the number of boards for day 4 [default: about the
+                             size of the puzzle input]
+
+VIZ OPTIONS:
+    -i, --input <PATH>       read input from PATH instead of `inputs/dayNN.txt`
+        --fps <N>            frames per second, 1 to 120 [default: 10]
+        --paused             start paused
+        --width <COLS>       width of the terminal [default: $COLUMNS or 80]
+        --height <ROWS>      height of the terminal [default: $LINES or 24]
+
+`aoc viz` animates days 5, 9, 11 and 13 in the terminal. While it runs, type a control
+and press Enter: `p` pauses or resumes, an empty line shows the next frame while paused,
+`+` and `-` change the speed, and `q` quits.
+
+IMAGE OPTIONS:
+    -o, --output <PATH>      write the image to PATH instead of STDOUT
+    -f, --format <FORMAT>    ppm, svg or png (with the `png` feature)
+                             [default: the extension of --output]
+        --scale <N>          size of each cell in pixels [default: 4]
+    -i, --input <PATH|->     read input from PATH, or STDIN with `-`,
+                             instead of `inputs/dayNN.txt`
+
+`aoc image` draws days 5, 9, 11 and 13: the overlapping vents, the basins, the number
+of flashes of each octopus in 100 steps, and the folded paper.
+
+`aoc depth-report` prints statistics of the day 1 sonar sweep: the longest increasing run,
+the largest drop, the positions of the decreases and the averages of each N measurements
+[default: 3], as a table or, with --json, every value as JSON.
+
+`aoc trajectory` follows the day 2 course in both parts, and writes the position and aim
+after each command as CSV, or the depth against the horizontal position as an SVG plot.
+The format is the extension of --output [default: csv].
+
+`aoc new` creates `src/dayNN.rs` and `examples/dayNN/example.{txt,toml}`, and registers
+the day in `src/lib.rs`. Existing files are never overwritten.
+";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ArgsError {
+    #[error("missing command")]
+    MissingCommand,
+
+    #[error("unknown command: {0}")]
+    UnknownCommand(String),
+
+    #[error("unknown option: {0}")]
+    UnknownOption(String),
+
+    #[error("missing value for {0}")]
+    MissingValue(String),
+
+    #[error("invalid part `{0}`: expected 1 or 2")]
+    InvalidPart(String),
+
+    #[error("invalid days `{0}`: expected a day, a range like `1..=13` or `all`")]
+    InvalidDays(String),
+
+    #[error("invalid format `{0}`: expected text, json, csv or markdown")]
+    InvalidFormat(String),
+
+    #[error("invalid interval `{0}`: expected seconds")]
+    InvalidInterval(String),
+
+    #[error("invalid {0} `{1}`: expected a non-negative integer")]
+    InvalidNumber(&'static str, String),
+
+    #[error("invalid window `{0}`: expected a positive integer")]
+    InvalidWindow(String),
+
+    #[error("no days given")]
+    MissingDays,
+
+    #[error("--input can only be used with a single day")]
+    InputWithManyDays,
+
+    #[error("--stream with --input - needs --part, STDIN can only be read once")]
+    StreamStdinWithoutPart,
+
+    #[error("--stream can't be used with --check")]
+    StreamWithCheck,
+
+    #[error("--window can only be used with day 1")]
+    WindowWithOtherDays,
+
+    #[error("--window can't be used with --check")]
+    WindowWithCheck,
+
+    #[error("--window only changes part 2, it can't be used with --part 1")]
+    WindowWithPartOne,
+
+    #[error("viz can't read input from STDIN, it reads the controls")]
+    VizStdin,
+
+    #[error(transparent)]
+    InvalidImageFormat(#[from] image_export::UnknownFormat),
+
+    #[error("--format is needed to write an image to STDOUT")]
+    MissingImageFormat,
+
+    #[error("invalid trajectory format `{0}`: expected csv or svg")]
+    InvalidTrajectoryFormat(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => f.write_str("1"),
+            Part::Two => f.write_str("2"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Input {
+    Stdin,
+    Path(PathBuf),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Days {
+    All,
+    Range(RangeInclusive<u8>),
+}
+
+impl std::str::FromStr for Days {
+    type Err = ArgsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ArgsError::InvalidDays(s.to_owned());
+        let day = |d: &str| d.parse::<u8>().map_err(|_| invalid());
+        if s == "all" {
+            return Ok(Days::All);
+        }
+        let range = if let Some((a, b)) = s.split_once("..=") {
+            day(a)?..=day(b)?
+        } else if let Some((a, b)) = s.split_once("..") {
+            let b = day(b)?.checked_sub(1).ok_or_else(invalid)?;
+            day(a)?..=b
+        } else {
+            let d = day(s)?;
+            d..=d
+        };
+        // An empty range would run nothing and succeed.
+        if range.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Days::Range(range))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunOptions {
+    pub days: Vec<Days>,
+    pub part: Option<Part>,
+    pub input: Option<Input>,
+    pub check: bool,
+    pub answers: PathBuf,
+    pub format: Format,
+    pub picture: bool,
+    pub stream: bool,
+    /// Width of the sliding window of day 1 part 2, instead of 3.
+    pub window: Option<usize>,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            days: Vec::new(),
+            part: None,
+            input: None,
+            check: false,
+            answers: PathBuf::from("answers.toml"),
+            format: Format::Text,
+            picture: false,
+            stream: false,
+            window: None,
+        }
+    }
+}
+
+impl RunOptions {
+    /// Returns `true` if `part` should be run.
+    pub fn runs(&self, part: Part) -> bool {
+        self.part.map_or(true, |p| p == part)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FetchOptions {
+    pub days: Vec<Days>,
+    pub base_url: String,
+    pub cache: PathBuf,
+    pub interval: Duration,
+    pub force: bool,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            days: Vec::new(),
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            cache: PathBuf::from("inputs"),
+            interval: Duration::from_secs(1),
+            force: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GenerateOptions {
+    pub day: u8,
+    pub seed: u64,
+    /// Defaults to the generator's size.
+    pub size: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VizOptions {
+    pub day: u8,
+    pub input: Option<PathBuf>,
+    pub fps: u32,
+    pub paused: bool,
+    /// Defaults to the size of the terminal.
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+}
+
+impl Default for VizOptions {
+    fn default() -> Self {
+        Self {
+            day: 0,
+            input: None,
+            fps: 10,
+            paused: false,
+            width: None,
+            height: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageOptions {
+    pub day: u8,
+    pub input: Option<Input>,
+    /// Writes to STDOUT if `None`.
+    pub output: Option<PathBuf>,
+    pub format: image_export::Format,
+    pub scale: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DepthReportOptions {
+    pub input: Option<Input>,
+    pub window: usize,
+    pub json: bool,
+}
+
+impl Default for DepthReportOptions {
+    fn default() -> Self {
+        Self {
+            input: None,
+            window: 3,
+            json: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrajectoryFormat {
+    Csv,
+    Svg,
+}
+
+impl std::str::FromStr for TrajectoryFormat {
+    type Err = ArgsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(TrajectoryFormat::Csv),
+            "svg" => Ok(TrajectoryFormat::Svg),
+            _ => Err(ArgsError::InvalidTrajectoryFormat(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrajectoryOptions {
+    pub input: Option<Input>,
+    /// Writes to STDOUT if `None`.
+    pub output: Option<PathBuf>,
+    pub format: TrajectoryFormat,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Run(RunOptions),
+    Fetch(FetchOptions),
+    New(u8),
+    Generate(GenerateOptions),
+    Viz(VizOptions),
+    Image(ImageOptions),
+    DepthReport(DepthReportOptions),
+    Trajectory(TrajectoryOptions),
+    Help,
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, ArgsError> {
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        Some("run") => parse_run(args).map(Command::Run),
+        Some("fetch") => parse_fetch(args).map(Command::Fetch),
+        Some("new") => parse_new(args).map(Command::New),
+        Some("gen-input") => parse_generate(args).map(Command::Generate),
+        Some("viz") => parse_viz(args).map(Command::Viz),
+        Some("image") => parse_image(args).map(Command::Image),
+        Some("depth-report") => parse_depth_report(args).map(Command::DepthReport),
+        Some("trajectory") => parse_trajectory(args).map(Command::Trajectory),
+        Some("help" | "-h" | "--help") => Ok(Command::Help),
+        Some(cmd) => Err(ArgsError::UnknownCommand(cmd.to_owned())),
+        None => Err(ArgsError::MissingCommand),
+    }
+}
+
+fn parse_run(mut args: impl Iterator<Item = String>) -> Result<RunOptions, ArgsError> {
+    let mut opts = RunOptions::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-p" | "--part" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.part = Some(match v.as_str() {
+                    "1" => Part::One,
+                    "2" => Part::Two,
+                    _ => return Err(ArgsError::InvalidPart(v)),
+                });
+            }
+            "-i" | "--input" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.input = Some(parse_input(v));
+            }
+            "-f" | "--format" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.format = v.parse()?;
+            }
+            "--check" => opts.check = true,
+            "--picture" => opts.picture = true,
+            "--stream" => opts.stream = true,
+            "--answers" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.answers = v.into();
+            }
+            "--window" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.window = Some(parse_window(v)?);
+            }
+            s if s.starts_with('-') => return Err(ArgsError::UnknownOption(arg)),
+            s => opts.days.push(s.parse()?),
+        }
+    }
+
+    if opts.days.is_empty() {
+        return Err(ArgsError::MissingDays);
+    }
+    let single = matches!(opts.days.as_slice(), [Days::Range(r)] if r.start() == r.end());
+    if opts.input.is_some() && !single {
+        return Err(ArgsError::InputWithManyDays);
+    }
+    if opts.stream && opts.check {
+        return Err(ArgsError::StreamWithCheck);
+    }
+    if opts.stream && opts.input == Some(Input::Stdin) && opts.part.is_none() {
+        return Err(ArgsError::StreamStdinWithoutPart);
+    }
+    if opts.window.is_some() && opts.days != [Days::Range(1..=1)] {
+        return Err(ArgsError::WindowWithOtherDays);
+    }
+    if opts.window.is_some() && opts.check {
+        return Err(ArgsError::WindowWithCheck);
+    }
+    if opts.window.is_some() && opts.part == Some(Part::One) {
+        return Err(ArgsError::WindowWithPartOne);
+    }
+    Ok(opts)
+}
+
+/// `-` for STDIN, or a path.
+fn parse_input(v: String) -> Input {
+    if v == "-" {
+        Input::Stdin
+    } else {
+        Input::Path(v.into())
+    }
+}
+
+// A window of 0 compares each measurement with itself.
+fn parse_window(v: String) -> Result<usize, ArgsError> {
+    match v.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(ArgsError::InvalidWindow(v)),
+    }
+}
+
+fn parse_fetch(mut args: impl Iterator<Item = String>) -> Result<FetchOptions, ArgsError> {
+    let mut opts = FetchOptions::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--base-url" => opts.base_url = args.next().ok_or(ArgsError::MissingValue(arg))?,
+            "--cache" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.cache = v.into();
+            }
+            "--interval" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.interval = v
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|s| s.is_finite() && *s >= 0.0)
+                    .map(Duration::from_secs_f64)
+                    .ok_or(ArgsError::InvalidInterval(v))?;
+            }
+            "--force" => opts.force = true,
+            s if s.starts_with('-') => return Err(ArgsError::UnknownOption(arg)),
+            s => opts.days.push(s.parse()?),
+        }
+    }
+
+    if opts.days.is_empty() {
+        return Err(ArgsError::MissingDays);
+    }
+    Ok(opts)
+}
+
+fn parse_new(mut args: impl Iterator<Item = String>) -> Result<u8, ArgsError> {
+    let arg = args.next().ok_or(ArgsError::MissingDays)?;
+    if let Some(extra) = args.next() {
+        return Err(ArgsError::UnknownOption(extra));
+    }
+    parse_day(arg)
+}
+
+fn parse_day(arg: String) -> Result<u8, ArgsError> {
+    arg.parse()
+        .ok()
+        .filter(|d| (1..=25).contains(d))
+        .ok_or(ArgsError::InvalidDays(arg))
+}
+
+fn parse_generate(mut args: impl Iterator<Item = String>) -> Result<GenerateOptions, ArgsError> {
+    let mut day = None;
+    let mut opts = GenerateOptions::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.seed = v.parse().map_err(|_| ArgsError::InvalidNumber("seed", v))?;
+            }
+            "--size" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.size = Some(v.parse().map_err(|_| ArgsError::InvalidNumber("size", v))?);
+            }
+            s if s.starts_with('-') => return Err(ArgsError::UnknownOption(arg)),
+            _ if day.is_some() => return Err(ArgsError::UnknownOption(arg)),
+            _ => day = Some(parse_day(arg)?),
+        }
+    }
+    opts.day = day.ok_or(ArgsError::MissingDays)?;
+    Ok(opts)
+}
+
+fn parse_viz(mut args: impl Iterator<Item = String>) -> Result<VizOptions, ArgsError> {
+    let mut day = None;
+    let mut opts = VizOptions::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-i" | "--input" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.input = match parse_input(v) {
+                    Input::Stdin => return Err(ArgsError::VizStdin),
+                    Input::Path(path) => Some(path),
+                };
+            }
+            "--fps" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.fps = v.parse().map_err(|_| ArgsError::InvalidNumber("fps", v))?;
+            }
+            "--paused" => opts.paused = true,
+            "--width" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.width = Some(
+                    v.parse()
+                        .map_err(|_| ArgsError::InvalidNumber("width", v))?,
+                );
+            }
+            "--height" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.height = Some(
+                    v.parse()
+                        .map_err(|_| ArgsError::InvalidNumber("height", v))?,
+                );
+            }
+            s if s.starts_with('-') => return Err(ArgsError::UnknownOption(arg)),
+            _ if day.is_some() => return Err(ArgsError::UnknownOption(arg)),
+            _ => day = Some(parse_day(arg)?),
+        }
+    }
+    opts.day = day.ok_or(ArgsError::MissingDays)?;
+    Ok(opts)
+}
+
+fn parse_image(mut args: impl Iterator<Item = String>) -> Result<ImageOptions, ArgsError> {
+    let mut day = None;
+    let mut input = None;
+    let mut output = None;
+    let mut format = None;
+    let mut scale = 4;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-i" | "--input" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                input = Some(parse_input(v));
+            }
+            "-o" | "--output" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                output = Some(PathBuf::from(v));
+            }
+            "-f" | "--format" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                format = Some(v.parse()?);
+            }
+            "--scale" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                scale = v
+                    .parse()
+                    .map_err(|_| ArgsError::InvalidNumber("scale", v))?;
+            }
+            s if s.starts_with('-') => return Err(ArgsError::UnknownOption(arg)),
+            _ if day.is_some() => return Err(ArgsError::UnknownOption(arg)),
+            _ => day = Some(parse_day(arg)?),
+        }
+    }
+    let format = match (format, &output) {
+        (Some(format), _) => format,
+        (None, Some(path)) => image_export::Format::from_path(path)?,
+        (None, None) => return Err(ArgsError::MissingImageFormat),
+    };
+    Ok(ImageOptions {
+        day: day.ok_or(ArgsError::MissingDays)?,
+        input,
+        output,
+        format,
+        scale,
+    })
+}
+
+fn parse_depth_report(
+    mut args: impl Iterator<Item = String>,
+) -> Result<DepthReportOptions, ArgsError> {
+    let mut opts = DepthReportOptions::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-i" | "--input" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.input = Some(parse_input(v));
+            }
+            "--window" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                opts.window = parse_window(v)?;
+            }
+            "--json" => opts.json = true,
+            _ => return Err(ArgsError::UnknownOption(arg)),
+        }
+    }
+    Ok(opts)
+}
+
+fn parse_trajectory(
+    mut args: impl Iterator<Item = String>,
+) -> Result<TrajectoryOptions, ArgsError> {
+    let mut input = None;
+    let mut output = None;
+    let mut format = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-i" | "--input" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                input = Some(parse_input(v));
+            }
+            "-o" | "--output" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                output = Some(PathBuf::from(v));
+            }
+            "-f" | "--format" => {
+                let v = args.next().ok_or(ArgsError::MissingValue(arg))?;
+                format = Some(v.parse()?);
+            }
+            _ => return Err(ArgsError::UnknownOption(arg)),
+        }
+    }
+    let format = match (format, &output) {
+        (Some(format), _) => format,
+        (None, Some(path)) => path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .parse()?,
+        (None, None) => TrajectoryFormat::Csv,
+    };
+    Ok(TrajectoryOptions {
+        input,
+        output,
+        format,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<Command, ArgsError> {
+        parse_args(s.split_whitespace().map(str::to_owned))
+    }
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!("all".parse(), Ok(Days::All));
+        assert_eq!("5".parse(), Ok(Days::Range(5..=5)));
+        assert_eq!("1..=13".parse(), Ok(Days::Range(1..=13)));
+        assert_eq!("1..14".parse(), Ok(Days::Range(1..=13)));
+        assert!("1..0".parse::<Days>().is_err());
+        assert_eq!(
+            "5..=3".parse::<Days>(),
+            Err(ArgsError::InvalidDays("5..=3".to_owned()))
+        );
+        assert_eq!(
+            "1..1".parse::<Days>(),
+            Err(ArgsError::InvalidDays("1..1".to_owned()))
+        );
+        assert!("x".parse::<Days>().is_err());
+    }
+
+    #[test]
+    fn test_parse_run() {
+        assert_eq!(
+            parse("run 5 --part 2 --input -"),
+            Ok(Command::Run(RunOptions {
+                days: vec![Days::Range(5..=5)],
+                part: Some(Part::Two),
+                input: Some(Input::Stdin),
+                ..RunOptions::default()
+            }))
+        );
+        assert_eq!(
+            parse("run all --check --answers a.toml"),
+            Ok(Command::Run(RunOptions {
+                days: vec![Days::All],
+                check: true,
+                answers: "a.toml".into(),
+                ..RunOptions::default()
+            }))
+        );
+        assert_eq!(
+            parse("run 1..=13 --format csv"),
+            Ok(Command::Run(RunOptions {
+                days: vec![Days::Range(1..=13)],
+                format: Format::Csv,
+                ..RunOptions::default()
+            }))
+        );
+        assert_eq!(parse("run"), Err(ArgsError::MissingDays));
+        assert_eq!(parse("fetch"), Err(ArgsError::MissingDays));
+        assert_eq!(parse("new 14"), Ok(Command::New(14)));
+        assert_eq!(
+            parse("new 26"),
+            Err(ArgsError::InvalidDays("26".to_owned()))
+        );
+        assert_eq!(parse("run all -i x"), Err(ArgsError::InputWithManyDays));
+        assert_eq!(
+            parse("run 5 --stream -i - -p 1"),
+            Ok(Command::Run(RunOptions {
+                days: vec![Days::Range(5..=5)],
+                part: Some(Part::One),
+                input: Some(Input::Stdin),
+                stream: true,
+                ..RunOptions::default()
+            }))
+        );
+        assert_eq!(
+            parse("run 5 --stream -i -"),
+            Err(ArgsError::StreamStdinWithoutPart)
+        );
+        assert_eq!(
+            parse("run all --stream --check"),
+            Err(ArgsError::StreamWithCheck)
+        );
+        assert_eq!(
+            parse("run 1 --window 5 -p 2"),
+            Ok(Command::Run(RunOptions {
+                days: vec![Days::Range(1..=1)],
+                part: Some(Part::Two),
+                window: Some(5),
+                ..RunOptions::default()
+            }))
+        );
+        assert_eq!(
+            parse("run 1..=2 --window 5"),
+            Err(ArgsError::WindowWithOtherDays)
+        );
+        assert_eq!(
+            parse("run 1 --window 5 --check"),
+            Err(ArgsError::WindowWithCheck)
+        );
+        assert_eq!(
+            parse("run 1 --window 0"),
+            Err(ArgsError::InvalidWindow("0".to_owned()))
+        );
+        assert_eq!(
+            parse("run 1 --window 5 --part 1"),
+            Err(ArgsError::WindowWithPartOne)
+        );
+        assert_eq!(
+            parse("run 1 --part 3"),
+            Err(ArgsError::InvalidPart("3".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_parse_fetch() {
+        assert_eq!(
+            parse("fetch 1..=3 --base-url http://localhost:8080 --cache /tmp/in --interval 0.5 --force"),
+            Ok(Command::Fetch(FetchOptions {
+                days: vec![Days::Range(1..=3)],
+                base_url: "http://localhost:8080".to_owned(),
+                cache: "/tmp/in".into(),
+                interval: Duration::from_millis(500),
+                force: true,
+            }))
+        );
+        assert_eq!(
+            parse("fetch 1 --interval -1"),
+            Err(ArgsError::InvalidInterval("-1".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_parse_viz() {
+        assert_eq!(
+            parse("viz 13 --fps 2 --paused --width 100 -i ex.txt"),
+            Ok(Command::Viz(VizOptions {
+                day: 13,
+                input: Some("ex.txt".into()),
+                fps: 2,
+                paused: true,
+                width: Some(100),
+                ..VizOptions::default()
+            }))
+        );
+        assert_eq!(parse("viz"), Err(ArgsError::MissingDays));
+        assert_eq!(parse("viz 5 -i -"), Err(ArgsError::VizStdin));
+        assert_eq!(
+            parse("viz 5 --fps fast"),
+            Err(ArgsError::InvalidNumber("fps", "fast".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_parse_image() {
+        assert_eq!(
+            parse("image 9 -o basins.svg --scale 8"),
+            Ok(Command::Image(ImageOptions {
+                day: 9,
+                input: None,
+                output: Some("basins.svg".into()),
+                format: image_export::Format::Svg,
+                scale: 8,
+            }))
+        );
+        assert_eq!(
+            parse("image 5 --format ppm -i -"),
+            Ok(Command::Image(ImageOptions {
+                day: 5,
+                input: Some(Input::Stdin),
+                output: None,
+                format: image_export::Format::Ppm,
+                scale: 4,
+            }))
+        );
+        assert_eq!(parse("image 5"), Err(ArgsError::MissingImageFormat));
+        assert_eq!(
+            parse("image 5 -o out.gif"),
+            Err(ArgsError::InvalidImageFormat(image_export::UnknownFormat(
+                "gif".to_owned()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_parse_depth_report() {
+        assert_eq!(
+            parse("depth-report"),
+            Ok(Command::DepthReport(DepthReportOptions::default()))
+        );
+        assert_eq!(
+            parse("depth-report -i - --window 5 --json"),
+            Ok(Command::DepthReport(DepthReportOptions {
+                input: Some(Input::Stdin),
+                window: 5,
+                json: true,
+            }))
+        );
+        assert_eq!(
+            parse("depth-report 1"),
+            Err(ArgsError::UnknownOption("1".to_owned()))
+        );
+        assert_eq!(
+            parse("depth-report --window 0"),
+            Err(ArgsError::InvalidWindow("0".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_parse_trajectory() {
+        assert_eq!(
+            parse("trajectory"),
+            Ok(Command::Trajectory(TrajectoryOptions {
+                input: None,
+                output: None,
+                format: TrajectoryFormat::Csv,
+            }))
+        );
+        assert_eq!(
+            parse("trajectory -i - -o course.svg"),
+            Ok(Command::Trajectory(TrajectoryOptions {
+                input: Some(Input::Stdin),
+                output: Some("course.svg".into()),
+                format: TrajectoryFormat::Svg,
+            }))
+        );
+        assert_eq!(
+            parse("trajectory -o course.png"),
+            Err(ArgsError::InvalidTrajectoryFormat("png".to_owned()))
+        );
+    }
+}
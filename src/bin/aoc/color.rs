@@ -0,0 +1,31 @@
+//! ANSI colors for terminal output, disabled when `NO_COLOR` is set.
+
+#[derive(Clone, Copy, Debug)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+}
+
+impl Color {
+    fn code(self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+        }
+    }
+}
+
+pub fn enabled() -> bool {
+    std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Wraps `s` in the escape codes for `color`.
+pub fn paint(s: &str, color: Color) -> String {
+    if enabled() {
+        format!("\x1b[{}m{}\x1b[0m", color.code(), s)
+    } else {
+        s.to_owned()
+    }
+}
@@ -0,0 +1,18 @@
+use aoc2021::{
+    day01::{Day01, DepthReport},
+    get_day, Result, Solution,
+};
+
+use crate::{cli::DepthReportOptions, run::read_input};
+
+pub fn depth_report(opts: &DepthReportOptions) -> Result<()> {
+    let day = get_day(1).ok_or("no solution for day 1")?;
+    let depths = Day01::parse(&read_input(day, opts.input.as_ref())?)?;
+    let report = DepthReport::new(&depths, opts.window);
+    if opts.json {
+        println!("{}", report.to_json()?);
+    } else {
+        print!("{report}");
+    }
+    Ok(())
+}
@@ -0,0 +1,399 @@
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use aoc2021::Result;
+use thiserror::Error;
+
+use crate::cli::{Days, FetchOptions};
+
+const USER_AGENT: &str = "github.com/kazk/aoc-2021 (aoc fetch)";
+// Inputs are small, anything larger is not an input.
+const MAX_INPUT_LEN: u64 = 1 << 20;
+
+#[derive(Debug, Error)]
+pub enum FetchError {
+    #[error("missing session: set AOC_SESSION or add it to .env")]
+    MissingSession,
+
+    #[error("{url}: {status} {message}")]
+    Status {
+        url: String,
+        status: u16,
+        message: String,
+    },
+
+    #[error("{url}: got an HTML page instead of the input, is the session valid?")]
+    Html { url: String },
+
+    #[error("{url}: got an empty input")]
+    Empty { url: String },
+
+    #[error("{url}: got more than {MAX_INPUT_LEN} bytes, not an input")]
+    TooLarge { url: String },
+
+    #[error("{url}: {source}")]
+    Request {
+        url: String,
+        source: Box<ureq::Error>,
+    },
+
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Waits so that consecutive requests are at least `interval` apart.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last: None,
+        }
+    }
+
+    pub fn wait(&mut self) {
+        if let Some(last) = self.last {
+            if let Some(remaining) = self.interval.checked_sub(last.elapsed()) {
+                thread::sleep(remaining);
+            }
+        }
+        self.last = Some(Instant::now());
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already in the cache.
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Downloads puzzle inputs into a cache directory.
+pub struct Fetcher {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+    cache: PathBuf,
+    limiter: RateLimiter,
+}
+
+impl Fetcher {
+    pub fn new(base_url: &str, session: String, cache: PathBuf, interval: Duration) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .build();
+        Self {
+            agent,
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session,
+            cache,
+            limiter: RateLimiter::new(interval),
+        }
+    }
+
+    /// Path to the cached input of `day`, e.g., `inputs/day01.txt`.
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.cache.join(format!("day{day:02}.txt"))
+    }
+
+    /// Returns the cached input of `day`, downloading it first if missing or `force` is set.
+    pub fn fetch(&mut self, day: u8, force: bool) -> Result<Fetched, FetchError> {
+        let path = self.path(day);
+        if !force && path.is_file() {
+            return Ok(Fetched::Cached(path));
+        }
+
+        let url = format!("{}/2021/day/{}/input", self.base_url, day);
+        self.limiter.wait();
+        let input = self.download(&url)?;
+        write_atomic(&path, &input)?;
+        Ok(Fetched::Downloaded(path))
+    }
+
+    fn download(&self, url: &str) -> Result<String, FetchError> {
+        let response = match self
+            .agent
+            .get(url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call()
+        {
+            Ok(response) => response,
+            Err(ureq::Error::Status(status, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                return Err(FetchError::Status {
+                    url: url.to_owned(),
+                    status,
+                    message: body.lines().next().unwrap_or_default().to_owned(),
+                });
+            }
+            Err(err) => {
+                return Err(FetchError::Request {
+                    url: url.to_owned(),
+                    source: Box::new(err),
+                })
+            }
+        };
+
+        let html = response.content_type() == "text/html";
+        let mut body = String::new();
+        response
+            .into_reader()
+            .take(MAX_INPUT_LEN + 1)
+            .read_to_string(&mut body)
+            .map_err(|e| FetchError::Request {
+                url: url.to_owned(),
+                source: Box::new(e.into()),
+            })?;
+        if body.len() as u64 > MAX_INPUT_LEN {
+            return Err(FetchError::TooLarge {
+                url: url.to_owned(),
+            });
+        }
+        validate(url, html, body)
+    }
+}
+
+/// Rejects responses that can't be an input, e.g., the login page.
+fn validate(url: &str, html: bool, body: String) -> Result<String, FetchError> {
+    let start = body.trim_start();
+    if html || start.starts_with('<') || body.contains("Please log in") {
+        Err(FetchError::Html {
+            url: url.to_owned(),
+        })
+    } else if start.is_empty() {
+        Err(FetchError::Empty {
+            url: url.to_owned(),
+        })
+    } else {
+        Ok(body)
+    }
+}
+
+// Writes to a temporary file first so an interrupted download is never cached.
+fn write_atomic(path: &Path, contents: &str) -> Result<(), FetchError> {
+    let io = |source| FetchError::Io {
+        path: path.to_owned(),
+        source,
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io)?;
+    }
+    let tmp = path.with_extension("txt.tmp");
+    fs::write(&tmp, contents).map_err(io)?;
+    fs::rename(&tmp, path).map_err(io)
+}
+
+/// `AOC_SESSION` from the environment, or from `.env` in the current directory.
+fn load_session() -> Option<String> {
+    std::env::var("AOC_SESSION")
+        .ok()
+        .or_else(|| session_from_dotenv(&fs::read_to_string(".env").ok()?))
+        .filter(|s| !s.is_empty())
+}
+
+fn session_from_dotenv(s: &str) -> Option<String> {
+    s.lines().find_map(|line| {
+        let line = line.trim();
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "AOC_SESSION").then(|| {
+            value
+                .trim()
+                .trim_matches(|c| c == '"' || c == '\'')
+                .to_owned()
+        })
+    })
+}
+
+// Any day can be fetched, not only those with a solution.
+fn fetch_days(selected: &[Days]) -> Result<Vec<u8>> {
+    let mut days = std::collections::BTreeSet::new();
+    for s in selected {
+        match s {
+            Days::All => days.extend(aoc2021::DAYS.iter().map(|d| d.day)),
+            Days::Range(range) => days.extend(range.clone()),
+        }
+    }
+    match days.iter().find(|&&d| !(1..=25).contains(&d)) {
+        Some(d) => Err(format!("no puzzle for day {d}").into()),
+        None => Ok(days.into_iter().collect()),
+    }
+}
+
+pub fn fetch(opts: &FetchOptions) -> Result<()> {
+    let days = fetch_days(&opts.days)?;
+    let session = load_session().ok_or(FetchError::MissingSession)?;
+    let mut fetcher = Fetcher::new(&opts.base_url, session, opts.cache.clone(), opts.interval);
+    let mut failed = 0;
+    for day in days {
+        match fetcher.fetch(day, opts.force) {
+            Ok(Fetched::Cached(path)) => println!("{}: cached", path.display()),
+            Ok(Fetched::Downloaded(path)) => println!("{}: downloaded", path.display()),
+            Err(err) => {
+                eprintln!("day{day:02}: {err}");
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(format!("{failed} day(s) failed").into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::mpsc,
+    };
+
+    /// Serves `responses` in order, sending each request line and cookie to the receiver.
+    fn stub_server(
+        responses: Vec<(&'static str, &'static str, String)>,
+    ) -> (String, mpsc::Receiver<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for (status, content_type, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                let mut cookie = String::new();
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(v) = header.strip_prefix("Cookie: ") {
+                        cookie = v.trim().to_owned();
+                    }
+                }
+                tx.send((request.trim().to_owned(), cookie)).unwrap();
+                // The client may hang up before reading a body that is too large.
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    content_type,
+                    body.len(),
+                    body
+                );
+            }
+        });
+        (url, rx)
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-fetch-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_fetch_and_cache() {
+        let (url, requests) = stub_server(vec![("200 OK", "text/plain", "1\n2\n3\n".to_owned())]);
+        let cache = cache_dir("cache");
+        let mut fetcher = Fetcher::new(&url, "abc".to_owned(), cache.clone(), Duration::ZERO);
+
+        let path = cache.join("day01.txt");
+        assert_eq!(
+            fetcher.fetch(1, false).unwrap(),
+            Fetched::Downloaded(path.clone())
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+        assert_eq!(
+            requests.recv().unwrap(),
+            (
+                "GET /2021/day/1/input HTTP/1.1".to_owned(),
+                "session=abc".to_owned()
+            )
+        );
+        // The stub only answers once, so this must come from the cache.
+        assert_eq!(fetcher.fetch(1, false).unwrap(), Fetched::Cached(path));
+        fs::remove_dir_all(cache).unwrap();
+    }
+
+    #[test]
+    fn test_reject_invalid() {
+        let (url, _requests) = stub_server(vec![
+            (
+                "400 Bad Request",
+                "text/plain",
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n"
+                    .to_owned(),
+            ),
+            (
+                "200 OK",
+                "text/html",
+                "<!DOCTYPE html><html></html>".to_owned(),
+            ),
+            ("200 OK", "text/plain", "<html>login</html>".to_owned()),
+            ("200 OK", "text/plain", "\n".to_owned()),
+            ("200 OK", "text/plain", "1\n".repeat(1 << 20)),
+        ]);
+        let cache = cache_dir("invalid");
+        let mut fetcher = Fetcher::new(&url, "abc".to_owned(), cache.clone(), Duration::ZERO);
+
+        assert!(matches!(
+            fetcher.fetch(1, false),
+            Err(FetchError::Status { status: 400, message, .. }) if message.starts_with("Puzzle inputs differ")
+        ));
+        assert!(matches!(
+            fetcher.fetch(1, false),
+            Err(FetchError::Html { .. })
+        ));
+        assert!(matches!(
+            fetcher.fetch(1, false),
+            Err(FetchError::Html { .. })
+        ));
+        assert!(matches!(
+            fetcher.fetch(1, false),
+            Err(FetchError::Empty { .. })
+        ));
+        assert!(matches!(
+            fetcher.fetch(1, false),
+            Err(FetchError::TooLarge { .. })
+        ));
+        assert!(!fetcher.path(1).exists());
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = RateLimiter::new(Duration::from_millis(50));
+        let start = Instant::now();
+        limiter.wait();
+        assert!(start.elapsed() < Duration::from_millis(50));
+        limiter.wait();
+        limiter.wait();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_session_from_dotenv() {
+        assert_eq!(
+            session_from_dotenv("# comment\nFOO=1\nexport AOC_SESSION=\"53616c\"\n"),
+            Some("53616c".to_owned())
+        );
+        assert_eq!(session_from_dotenv("FOO=1"), None);
+    }
+}
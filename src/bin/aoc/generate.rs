@@ -0,0 +1,16 @@
+use std::io::{BufWriter, Write};
+
+use aoc2021::{generate, Result};
+
+use crate::cli::GenerateOptions;
+
+pub fn generate(opts: &GenerateOptions) -> Result<()> {
+    let generator = generate::get_generator(opts.day)
+        .ok_or_else(|| format!("no input generator for day {}", opts.day))?;
+    let size = opts.size.unwrap_or(generator.default_size);
+    let stdout = std::io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    generator.write(opts.seed, size, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use aoc2021::{get_day, image_export, Result};
+
+use crate::{cli::ImageOptions, run::read_input};
+
+pub fn image(opts: &ImageOptions) -> Result<()> {
+    let day = get_day(opts.day).ok_or_else(|| format!("no solution for day {}", opts.day))?;
+    let input = read_input(day, opts.input.as_ref())?;
+    let pixels = image_export::image(opts.day, &input).ok_or_else(|| {
+        let days = image_export::days()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("no image for day {} (available: {})", opts.day, days)
+    })??;
+
+    let mut out: Box<dyn Write> = match &opts.output {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).map_err(|e| {
+                format!("failed to create {}: {}", path.display(), e)
+            })?))
+        }
+        None => Box::new(BufWriter::new(std::io::stdout())),
+    };
+    image_export::write(&pixels, opts.format, opts.scale, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
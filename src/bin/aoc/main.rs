@@ -0,0 +1,57 @@
+#![forbid(unsafe_code)]
+#![deny(clippy::all)]
+#![deny(clippy::pedantic)]
+
+mod check;
+mod cli;
+mod color;
+mod depths;
+#[cfg(feature = "fetch")]
+mod fetch;
+mod generate;
+mod image;
+mod report;
+mod run;
+mod scaffold;
+mod trajectory;
+mod viz;
+
+use cli::Command;
+
+fn main() {
+    let cmd = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let result = match cmd {
+        Command::Run(opts) => run::run(&opts),
+        #[cfg(feature = "fetch")]
+        Command::Fetch(opts) => fetch::fetch(&opts),
+        #[cfg(not(feature = "fetch"))]
+        Command::Fetch(_) => {
+            Err("`aoc fetch` needs the `fetch` feature, build with `--features fetch`".into())
+        }
+        Command::Generate(opts) => generate::generate(&opts),
+        Command::Viz(opts) => viz::viz(&opts),
+        Command::Image(opts) => image::image(&opts),
+        Command::DepthReport(opts) => depths::depth_report(&opts),
+        Command::Trajectory(opts) => trajectory::trajectory(&opts),
+        Command::New(day) => scaffold::scaffold(std::path::Path::new("."), day).map(|paths| {
+            for path in paths {
+                println!("{}", path.display());
+            }
+        }),
+        Command::Help => {
+            print!("{}", cli::USAGE);
+            Ok(())
+        }
+    };
+    if let Err(err) = result {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,160 @@
+use std::{fmt::Write, io, time::Duration};
+
+use serde::Serialize;
+
+use aoc2021::Answer;
+
+use crate::cli::{ArgsError, Part};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl std::str::FromStr for Format {
+    type Err = ArgsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "markdown" | "md" => Ok(Format::Markdown),
+            _ => Err(ArgsError::InvalidFormat(s.to_owned())),
+        }
+    }
+}
+
+/// Answer and timings of a part. The parse time is shared by both parts of a day.
+#[derive(Clone, Debug, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub parse_ns: u64,
+    pub solve_ns: u64,
+}
+
+impl Record {
+    pub fn new(day: u8, part: Part, answer: &Answer, parse: Duration, solve: Duration) -> Self {
+        Self {
+            day,
+            part: match part {
+                Part::One => 1,
+                Part::Two => 2,
+            },
+            answer: answer.to_string(),
+            parse_ns: nanos(parse),
+            solve_ns: nanos(solve),
+        }
+    }
+}
+
+fn nanos(d: Duration) -> u64 {
+    u64::try_from(d.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Writes a record as the text format does, as soon as it's available.
+pub fn write_text(out: &mut dyn io::Write, record: &Record) -> io::Result<()> {
+    let took = Duration::from_nanos(record.solve_ns);
+    if record.answer.contains('\n') {
+        writeln!(
+            out,
+            "  part {}: took {:?}\n{}",
+            record.part, took, record.answer
+        )
+    } else {
+        writeln!(
+            out,
+            "  part {}: {} (took {:?})",
+            record.part, record.answer, took
+        )
+    }
+}
+
+pub fn to_json(records: &[Record]) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(records)
+}
+
+pub fn to_csv(records: &[Record]) -> String {
+    let mut out = String::from("day,part,answer,parse_ns,solve_ns\n");
+    for r in records {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{}",
+            r.day,
+            r.part,
+            csv_field(&r.answer),
+            r.parse_ns,
+            r.solve_ns
+        );
+    }
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+pub fn to_markdown(records: &[Record]) -> String {
+    let mut out = String::from("| Day | Part | Answer | Parse | Solve |\n");
+    out.push_str("| ---: | ---: | :--- | ---: | ---: |\n");
+    for r in records {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {:?} | {:?} |",
+            r.day,
+            r.part,
+            r.answer.replace('|', "\\|").replace('\n', "<br>"),
+            Duration::from_nanos(r.parse_ns),
+            Duration::from_nanos(r.solve_ns)
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        let ms = Duration::from_millis;
+        vec![
+            Record::new(7, Part::One, &Answer::Number(37), ms(1), ms(2)),
+            Record::new(13, Part::Two, &Answer::Text("#.\n.#".into()), ms(1), ms(3)),
+        ]
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            to_csv(&records()),
+            "day,part,answer,parse_ns,solve_ns\n\
+             7,1,37,1000000,2000000\n\
+             13,2,\"#.\n.#\",1000000,3000000\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(
+            to_markdown(&records()).lines().nth(3),
+            Some("| 13 | 2 | #.<br>.# | 1ms | 3ms |")
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let json = to_json(&records()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[0]["answer"], "37");
+        assert_eq!(value[1]["solve_ns"], 3_000_000);
+    }
+}
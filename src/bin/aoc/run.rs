@@ -0,0 +1,182 @@
+use std::{
+    collections::BTreeSet,
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use aoc2021::{day01, Answer, Day, Result, DAYS};
+
+use crate::{
+    cli::{Days, Input, Part, RunOptions},
+    report::{self, Format, Record},
+};
+
+pub fn run(opts: &RunOptions) -> Result<()> {
+    if opts.check {
+        return crate::check::check(opts);
+    }
+
+    let mut failed = 0;
+    let mut records = Vec::new();
+    let mut report = |day: &Day, result: Result<Vec<Record>, String>| match result {
+        Ok(rs) => records.extend(rs),
+        Err(err) => {
+            eprintln!("{}: {}", day.name(), err);
+            failed += 1;
+        }
+    };
+    let days = select_days(&opts.days)?;
+    if cfg!(feature = "parallel") && days.len() > 1 {
+        // Each day writes its text to a buffer, printed in order once all days are done.
+        let results = aoc2021::par::map(&days, |day| {
+            let mut out = Vec::new();
+            let result = solve_day(day, opts, &mut out).map_err(|e| e.to_string());
+            (out, result)
+        });
+        for (day, (out, result)) in days.iter().zip(results) {
+            io::stdout().write_all(&out)?;
+            report(day, result);
+        }
+    } else {
+        for day in days {
+            let result = solve_day(day, opts, &mut io::stdout()).map_err(|e| e.to_string());
+            report(day, result);
+        }
+    }
+    match opts.format {
+        Format::Text => {}
+        Format::Json => println!("{}", report::to_json(&records)?),
+        Format::Csv => print!("{}", report::to_csv(&records)),
+        Format::Markdown => print!("{}", report::to_markdown(&records)),
+    }
+    if failed > 0 {
+        return Err(format!("{failed} day(s) failed").into());
+    }
+    Ok(())
+}
+
+/// Resolves the selected days in order, without duplicates.
+pub fn select_days(selected: &[Days]) -> Result<Vec<&'static Day>> {
+    let mut days = BTreeSet::new();
+    for s in selected {
+        match s {
+            Days::All => days.extend(DAYS.iter().map(|d| d.day)),
+            Days::Range(range) => days.extend(range.clone()),
+        }
+    }
+    days.into_iter()
+        .map(|d| aoc2021::get_day(d).ok_or_else(|| format!("no solution for day {d}").into()))
+        .collect()
+}
+
+pub fn read_input(day: &Day, input: Option<&Input>) -> Result<String> {
+    match input {
+        Some(Input::Stdin) => {
+            let mut s = String::new();
+            std::io::stdin().read_to_string(&mut s)?;
+            Ok(s)
+        }
+        Some(Input::Path(path)) => std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e).into()),
+        None => {
+            let path = day.input_path();
+            std::fs::read_to_string(&path)
+                .map_err(|e| format!("failed to read {}: {}", path.display(), e).into())
+        }
+    }
+}
+
+/// Opens the input to read it one line at a time.
+pub fn open_input(day: &Day, input: Option<&Input>) -> Result<Box<dyn BufRead>> {
+    let open = |path: &Path| -> Result<Box<dyn BufRead>> {
+        let file =
+            File::open(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        Ok(Box::new(BufReader::new(file)))
+    };
+    match input {
+        Some(Input::Stdin) => Ok(Box::new(BufReader::new(std::io::stdin()))),
+        Some(Input::Path(path)) => open(path),
+        None => open(&day.input_path()),
+    }
+}
+
+fn solve_day(day: &Day, opts: &RunOptions, out: &mut dyn Write) -> Result<Vec<Record>> {
+    if opts.stream {
+        stream_day(day, opts, out)
+    } else {
+        run_day(day, opts, out)
+    }
+}
+
+// Streaming days read and solve at once, so the time is all in solve.
+fn stream_day(day: &Day, opts: &RunOptions, out: &mut dyn Write) -> Result<Vec<Record>> {
+    if !day.can_stream() {
+        return Err("can't read its input as a stream".into());
+    }
+    let text = opts.format == Format::Text;
+    if text {
+        writeln!(out, "{}", day.name())?;
+    }
+
+    let mut records = Vec::new();
+    for part in [Part::One, Part::Two] {
+        if !opts.runs(part) {
+            continue;
+        }
+        let mut input = open_input(day, opts.input.as_ref())?;
+        let start = Instant::now();
+        let answer = match (part, opts.window) {
+            (Part::Two, Some(window)) => day01::stream_count_increases(&mut input, window)?.into(),
+            (Part::One, _) => day.stream_part1(&mut input)?,
+            (Part::Two, None) => day.stream_part2(&mut input)?,
+        };
+        let record = Record::new(day.day, part, &answer, Duration::ZERO, start.elapsed());
+        if text {
+            report::write_text(out, &record)?;
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn run_day(day: &Day, opts: &RunOptions, out: &mut dyn Write) -> Result<Vec<Record>> {
+    let input = read_input(day, opts.input.as_ref())?;
+    let text = opts.format == Format::Text;
+    if text {
+        writeln!(out, "{}", day.name())?;
+    }
+
+    let start = Instant::now();
+    // Only day 1 takes a window.
+    let parsed = match opts.window {
+        Some(window) => day01::parse_with_window(&input, window)?,
+        None => day.parse(&input)?,
+    };
+    let parse = start.elapsed();
+    if text {
+        writeln!(out, "  parse: took {parse:?}")?;
+    }
+
+    let mut records = Vec::new();
+    for part in [Part::One, Part::Two] {
+        if !opts.runs(part) {
+            continue;
+        }
+        let start = Instant::now();
+        let answer = match part {
+            Part::One => parsed.part1()?,
+            Part::Two => parsed.part2()?,
+        };
+        let record = Record::new(day.day, part, &answer, parse, start.elapsed());
+        if text {
+            report::write_text(out, &record)?;
+            if let (true, Answer::Letters(letters)) = (opts.picture, &answer) {
+                writeln!(out, "{}", letters.picture)?;
+            }
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
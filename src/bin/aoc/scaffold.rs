@@ -0,0 +1,218 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use aoc2021::Result;
+use thiserror::Error;
+
+const TEMPLATE: &str = r"use super::{Result, Solution};
+
+pub struct DayNN;
+
+impl Solution for DayNN {
+    type Input = Vec<String>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.lines().map(str::to_owned).collect())
+    }
+
+    fn part1(_input: &Self::Input) -> Result<u32> {
+        Ok(0)
+    }
+
+    fn part2(_input: &Self::Input) -> Result<u32> {
+        Ok(0)
+    }
+}
+
+pub fn part1(input: &str) -> Result<u32> {
+    DayNN::solve_part1(input)
+}
+
+pub fn part2(input: &str) -> Result<u32> {
+    DayNN::solve_part2(input)
+}
+";
+
+const EXAMPLE_ANSWERS: &str = "\
+# Expected answers for `example.txt`, checked by `cargo test --test examples`.
+# part1 = 0
+# part2 = 0
+";
+
+#[derive(Debug, Error)]
+pub enum ScaffoldError {
+    #[error("{} already exists", .0.display())]
+    Exists(PathBuf),
+
+    #[error("{0} is already registered in src/lib.rs")]
+    Registered(String),
+
+    #[error("`pub static DAYS` not found in src/lib.rs")]
+    MissingRegistry,
+}
+
+/// Creates the module and example fixture for `day` under `root` and registers it in `src/lib.rs`.
+///
+/// The fixture is `examples/dayNN/example.txt` with its answers in `example.toml`.
+///
+/// Nothing is written if any of the files already exists or the day is already registered.
+pub fn scaffold(root: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    let name = format!("day{day:02}");
+    let module = root.join("src").join(format!("{name}.rs"));
+    let example = root.join("examples").join(&name).join("example.txt");
+    let answers = example.with_extension("toml");
+    for path in [&module, &example, &answers] {
+        if path.exists() {
+            return Err(ScaffoldError::Exists(path.clone()).into());
+        }
+    }
+
+    let lib_path = root.join("src").join("lib.rs");
+    let lib = fs::read_to_string(&lib_path)?;
+    let lib = register(&lib, day)?;
+
+    let source = TEMPLATE
+        .replace("DayNN", &format!("Day{day:02}"))
+        .replace("dayNN", &name);
+    create_new(&module, &source)?;
+    fs::create_dir_all(example.parent().unwrap())?;
+    create_new(&example, "")?;
+    create_new(&answers, EXAMPLE_ANSWERS)?;
+    fs::write(&lib_path, lib)?;
+    Ok(vec![module, example, answers, lib_path])
+}
+
+fn create_new(path: &Path, contents: &str) -> Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| format!("failed to create {}: {}", path.display(), e))?;
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+/// Adds `pub mod dayNN;` and the entry in `DAYS` to `lib`, keeping both ordered by day.
+fn register(lib: &str, day: u8) -> Result<String, ScaffoldError> {
+    let name = format!("day{day:02}");
+    let module = format!("pub mod {name};");
+    let entry = format!("    {day} => {name}::Day{day:02},");
+
+    let mut lines = lib.lines().map(str::to_owned).collect::<Vec<_>>();
+    if lines.contains(&module) {
+        return Err(ScaffoldError::Registered(name));
+    }
+
+    let start = lines
+        .iter()
+        .position(|l| l.starts_with("pub static DAYS"))
+        .ok_or(ScaffoldError::MissingRegistry)?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|l| l.starts_with("];"))
+            .ok_or(ScaffoldError::MissingRegistry)?;
+    let entry_day = |l: &str| l.trim().split(" =>").next()?.parse::<u8>().ok();
+    if lines[start..end].iter().any(|l| entry_day(l) == Some(day)) {
+        return Err(ScaffoldError::Registered(name));
+    }
+    let at = (start + 1..end)
+        .find(|&i| entry_day(&lines[i]).is_some_and(|d| d > day))
+        .unwrap_or(end);
+    lines.insert(at, entry);
+
+    // Module names are zero-padded, so they sort by day.
+    let mods = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.starts_with("pub mod day"))
+        .map(|(i, l)| (i, l.as_str()))
+        .collect::<Vec<_>>();
+    let at = match mods.iter().find(|(_, l)| *l > module.as_str()) {
+        Some(&(i, _)) => i,
+        None => mods.last().map_or(start, |&(i, _)| i + 1),
+    };
+    lines.insert(at, module);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = indoc::indoc! {"
+        pub mod grid;
+
+        pub mod day01;
+        pub mod day03;
+
+        pub static DAYS: &[Day] = days![
+            1 => day01::Day01,
+            3 => day03::Day03,
+        ];
+    "};
+
+    #[test]
+    fn test_register() {
+        assert_eq!(
+            register(LIB, 2).unwrap(),
+            indoc::indoc! {"
+                pub mod grid;
+
+                pub mod day01;
+                pub mod day02;
+                pub mod day03;
+
+                pub static DAYS: &[Day] = days![
+                    1 => day01::Day01,
+                    2 => day02::Day02,
+                    3 => day03::Day03,
+                ];
+            "}
+        );
+        let lib = register(LIB, 10).unwrap();
+        assert!(lib.contains("pub mod day03;\npub mod day10;\n"));
+        assert!(lib.contains("    10 => day10::Day10,\n];"));
+        assert!(matches!(
+            register(LIB, 3),
+            Err(ScaffoldError::Registered(_))
+        ));
+        assert!(matches!(
+            register("pub mod day01;\n", 2),
+            Err(ScaffoldError::MissingRegistry)
+        ));
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+
+        scaffold(&root, 2).unwrap();
+        let source = fs::read_to_string(root.join("src/day02.rs")).unwrap();
+        assert!(source.contains("pub struct Day02;"));
+        assert!(root.join("examples/day02/example.txt").is_file());
+        assert!(root.join("examples/day02/example.toml").is_file());
+        assert!(fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("2 => day02::Day02,"));
+
+        // Refuses to overwrite an existing module, even if it's not registered.
+        fs::write(root.join("src/day04.rs"), "// mine").unwrap();
+        assert!(scaffold(&root, 4).is_err());
+        assert_eq!(
+            fs::read_to_string(root.join("src/day04.rs")).unwrap(),
+            "// mine"
+        );
+        assert!(!root.join("examples/day04").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt::Write as _;
+
+use aoc2021::{
+    day02::{self, State, Trajectories},
+    get_day,
+    visualize::{palette, Rgb},
+    Result,
+};
+
+use crate::{
+    cli::{TrajectoryFormat, TrajectoryOptions},
+    run::read_input,
+};
+
+pub fn trajectory(opts: &TrajectoryOptions) -> Result<()> {
+    let day = get_day(2).ok_or("no solution for day 2")?;
+    let trajectories = day02::trajectories(&read_input(day, opts.input.as_ref())?)?;
+    let out = match opts.format {
+        TrajectoryFormat::Csv => trajectories.to_csv(),
+        TrajectoryFormat::Svg => to_svg(&trajectories),
+    };
+    match &opts.output {
+        Some(path) => std::fs::write(path, out)
+            .map_err(|e| format!("failed to write {}: {}", path.display(), e))?,
+        None => print!("{out}"),
+    }
+    Ok(())
+}
+
+/// Plots the depth against the horizontal position as a line for each part,
+/// deeper toward the bottom. Both use the same axes.
+fn to_svg(trajectories: &Trajectories) -> String {
+    const WIDTH: f64 = 800.0;
+    const HEIGHT: f64 = 400.0;
+    const MARGIN: f64 = 40.0;
+
+    let parts = [
+        ("part 1", &trajectories.naive),
+        ("part 2", &trajectories.aim),
+    ];
+    let all = || parts.iter().flat_map(|(_, states)| states.iter());
+    let range = |f: fn(&State) -> i32| {
+        let min = all().map(f).min().unwrap_or_default();
+        let max = all().map(f).max().unwrap_or_default();
+        (f64::from(min), f64::from(max.max(min + 1)))
+    };
+    let (h_min, h_max) = range(|s| s.horizontal);
+    let (d_min, d_max) = range(|s| s.depth);
+    let to_x = |h: i32| MARGIN + (f64::from(h) - h_min) / (h_max - h_min) * (WIDTH - 2.0 * MARGIN);
+    let to_y = |d: i32| MARGIN + (f64::from(d) - d_min) / (d_max - d_min) * (HEIGHT - 2.0 * MARGIN);
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="12">"#
+    );
+    let _ = writeln!(
+        out,
+        r#"<rect width="{WIDTH}" height="{HEIGHT}" fill="white"/>"#
+    );
+    let _ = writeln!(
+        out,
+        r#"<text x="{}" y="{}" text-anchor="end">horizontal position {} to {}, depth {} to {}</text>"#,
+        WIDTH - MARGIN,
+        HEIGHT - 12.0,
+        h_min,
+        h_max,
+        d_min,
+        d_max
+    );
+    let mut legend_y = MARGIN / 2.0;
+    for (i, (part, states)) in parts.iter().enumerate() {
+        let Rgb(r, g, b) = palette(i * 3);
+        let points = states
+            .iter()
+            .map(|s| format!("{:.1},{:.1}", to_x(s.horizontal), to_y(s.depth)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(
+            out,
+            r##"<polyline points="{points}" fill="none" stroke="#{r:02x}{g:02x}{b:02x}" stroke-width="2"/>"##
+        );
+        let _ = writeln!(
+            out,
+            r##"<text x="{MARGIN}" y="{legend_y}" fill="#{r:02x}{g:02x}{b:02x}">{part}</text>"##
+        );
+        legend_y += 14.0;
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_svg() {
+        let example = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+        let svg = to_svg(&day02::trajectories(example).unwrap());
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        // Both start at the top left, the deepest point of part 2 is at the bottom right.
+        assert!(svg.contains(r#"points="40.0,40.0 "#));
+        assert!(svg.contains(r#" 760.0,360.0" "#));
+        assert!(svg.contains(">part 2</text>"));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
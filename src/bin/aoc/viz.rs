@@ -0,0 +1,84 @@
+use std::{
+    io::{BufRead, BufWriter},
+    sync::mpsc,
+    thread,
+};
+
+use aoc2021::{
+    get_day,
+    visualize::{self, Control, Options},
+    Result,
+};
+
+use crate::{
+    cli::{Input, VizOptions},
+    run::read_input,
+};
+
+pub fn viz(opts: &VizOptions) -> Result<()> {
+    let day = get_day(opts.day).ok_or_else(|| format!("no solution for day {}", opts.day))?;
+    let input = read_input(day, opts.input.clone().map(Input::Path).as_ref())?;
+    let mut renderer = visualize::renderer(opts.day, &input).ok_or_else(|| {
+        let days = visualize::days()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "no visualization for day {} (available: {})",
+            opts.day, days
+        )
+    })??;
+
+    // The thread stays blocked on STDIN after the animation ends, exiting with the process.
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let control = match line.as_deref().map(parse_control) {
+                Ok(Some(control)) => control,
+                Ok(None) => continue,
+                Err(_) => break,
+            };
+            if tx.send(control).is_err() {
+                break;
+            }
+        }
+    });
+
+    let env_size = |name: &str| std::env::var(name).ok().and_then(|v| v.parse().ok());
+    let options = Options {
+        fps: opts.fps,
+        paused: opts.paused,
+        width: opts.width.or_else(|| env_size("COLUMNS")).unwrap_or(80),
+        height: opts.height.or_else(|| env_size("LINES")).unwrap_or(24),
+    };
+    let stdout = std::io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    visualize::animate(renderer.as_mut(), &options, &rx, &mut out)?;
+    Ok(())
+}
+
+/// Reads a line typed while the animation runs.
+fn parse_control(line: &str) -> Option<Control> {
+    match line.trim() {
+        "" => Some(Control::Step),
+        "p" => Some(Control::Pause),
+        "+" => Some(Control::Faster),
+        "-" => Some(Control::Slower),
+        "q" => Some(Control::Quit),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_control() {
+        assert_eq!(parse_control(""), Some(Control::Step));
+        assert_eq!(parse_control(" p "), Some(Control::Pause));
+        assert_eq!(parse_control("+"), Some(Control::Faster));
+        assert_eq!(parse_control("q"), Some(Control::Quit));
+        assert_eq!(parse_control("x"), None);
+    }
+}
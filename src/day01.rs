@@ -1,38 +1,414 @@
-use super::Result;
+use std::{collections::VecDeque, fmt, io::BufRead};
 
-fn parse_depths(input: &str) -> Result<Vec<u32>, std::num::ParseIntError> {
-    input.lines().map(str::parse).collect()
+use serde::Serialize;
+
+use super::{
+    parse::{self, ParseError},
+    Answer, Parsed, Result, Solution, Streaming,
+};
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<u32>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_depths(input)?)
+    }
+
+    fn part1(depths: &Self::Input) -> Result<usize> {
+        Ok(count_increases(depths.iter().copied(), 1))
+    }
+
+    fn part2(depths: &Self::Input) -> Result<usize> {
+        Ok(count_increases(depths.iter().copied(), 3))
+    }
+}
+
+impl Streaming for Day01 {
+    fn stream_part1(input: &mut dyn BufRead) -> Result<usize> {
+        stream_count_increases(input, 1)
+    }
+
+    fn stream_part2(input: &mut dyn BufRead) -> Result<usize> {
+        stream_count_increases(input, 3)
+    }
+}
+
+fn parse_depth(s: &str) -> Result<u32, ParseError> {
+    s.parse().map_err(|e| ParseError::new(s, s, e))
+}
+
+fn parse_depths(input: &str) -> Result<Vec<u32>, ParseError> {
+    parse::lines(input, parse_depth)
+}
+
+/// Number of times the sum of `window` consecutive measurements increases.
+///
+/// Consecutive sums share all but the first and the last measurement, so comparing
+/// the sums is comparing measurements `window` apart, keeping only the last `window`.
+pub fn count_increases(depths: impl IntoIterator<Item = u32>, window: usize) -> usize {
+    let mut last = VecDeque::new();
+    let mut count = 0;
+    for depth in depths {
+        last.push_back(depth);
+        if last.len() > window && last.pop_front().is_some_and(|d| d < depth) {
+            count += 1;
+        }
+    }
+    count
 }
 
-fn increased_count<T: PartialOrd>(iter: impl Iterator<Item = T> + Clone) -> usize {
-    iter.clone()
-        .zip(iter.skip(1))
-        .filter(|(a, b)| a < b)
-        .count()
+/// # Errors
+///
+/// Will return `Err` if the input contains an invalid integer.
+pub fn part1(input: &str) -> Result<usize> {
+    Day01::solve_part1(input)
 }
 
 /// # Errors
 ///
 /// Will return `Err` if the input contains an invalid integer.
-pub fn part1(input: &str) -> Result<()> {
-    let depths = parse_depths(input)?;
-    let n = increased_count(depths.iter());
-    println!("{}", n);
-    Ok(())
+pub fn part2(input: &str) -> Result<usize> {
+    Day01::solve_part2(input)
 }
 
+/// Parses `input` and counts the increases of sums of `window` measurements.
+///
 /// # Errors
 ///
 /// Will return `Err` if the input contains an invalid integer.
-pub fn part2(input: &str) -> Result<()> {
-    let depths = parse_depths(input)?;
-    // sliding window of 3 measurements summed
-    let sum3 = depths
-        .iter()
-        .zip(depths.iter().skip(1))
-        .zip(depths.iter().skip(2))
-        .map(|((a, b), c)| a + b + c);
-    let n = increased_count(sum3);
-    println!("{}", n);
-    Ok(())
+pub fn count_increases_str(input: &str, window: usize) -> Result<usize> {
+    Ok(count_increases(parse_depths(input)?, window))
+}
+
+/// Counts the increases of sums of `window` measurements, reading one line at a time.
+///
+/// # Errors
+///
+/// Will return `Err` if the input contains an invalid integer or can't be read.
+pub fn stream_count_increases(input: &mut dyn BufRead, window: usize) -> Result<usize> {
+    parse::until_error(parse::read_lines(input, parse_depth), |depths| {
+        count_increases(depths, window)
+    })
+}
+
+/// Depths whose part 2 compares sums of `window` measurements instead of 3.
+struct Windowed {
+    depths: Vec<u32>,
+    window: usize,
+}
+
+impl Parsed for Windowed {
+    fn part1(&self) -> Result<Answer> {
+        Day01::part1(&self.depths).map(Into::into)
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        Ok(count_increases(self.depths.iter().copied(), self.window).into())
+    }
+}
+
+/// Parses `input` to be solved like [`Day01`], but with sums of `window` measurements
+/// in part 2.
+///
+/// # Errors
+///
+/// Will return `Err` if the input contains an invalid integer.
+pub fn parse_with_window(input: &str, window: usize) -> Result<Box<dyn Parsed>> {
+    Ok(Box::new(Windowed {
+        depths: parse_depths(input)?,
+        window,
+    }))
+}
+
+/// Measurements increasing one after the other.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Run {
+    /// Index of the first measurement.
+    pub start: usize,
+    /// Number of measurements.
+    pub len: usize,
+}
+
+/// Measurement shallower than the one before.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Decrease {
+    /// Index of the measurement.
+    pub at: usize,
+    pub from: u32,
+    pub to: u32,
+}
+
+impl Decrease {
+    #[must_use]
+    pub fn size(&self) -> u32 {
+        self.from - self.to
+    }
+}
+
+/// Statistics of a sonar sweep beyond the number of increases.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DepthReport {
+    pub measurements: usize,
+    pub increases: usize,
+    /// The first of the longest, empty without measurements.
+    pub longest_increasing_run: Run,
+    /// The first of the largest, `None` if the depths never decrease.
+    pub largest_drop: Option<Decrease>,
+    pub window: usize,
+    /// Averages of each `window` consecutive measurements.
+    pub moving_averages: Vec<f64>,
+    /// Indices of the measurements less than the one before.
+    pub decreases: Vec<usize>,
+}
+
+impl DepthReport {
+    #[must_use]
+    pub fn new(depths: &[u32], window: usize) -> Self {
+        let mut run = Run::default();
+        let mut longest = Run::default();
+        let mut largest_drop: Option<Decrease> = None;
+        let mut decreases = Vec::new();
+        for (i, &depth) in depths.iter().enumerate() {
+            match i.checked_sub(1).map(|j| depths[j]) {
+                Some(prev) if prev < depth => run.len += 1,
+                Some(prev) => {
+                    run = Run { start: i, len: 1 };
+                    if depth < prev {
+                        let drop = Decrease {
+                            at: i,
+                            from: prev,
+                            to: depth,
+                        };
+                        if largest_drop.map_or(true, |d| d.size() < drop.size()) {
+                            largest_drop = Some(drop);
+                        }
+                        decreases.push(i);
+                    }
+                }
+                None => run = Run { start: 0, len: 1 },
+            }
+            if longest.len < run.len {
+                longest = run;
+            }
+        }
+
+        DepthReport {
+            measurements: depths.len(),
+            increases: count_increases(depths.iter().copied(), 1),
+            longest_increasing_run: longest,
+            largest_drop,
+            window,
+            moving_averages: moving_averages(depths, window),
+            decreases,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+// Keeps a running sum, adding the measurement entering the window and
+// subtracting the one leaving it.
+fn moving_averages(depths: &[u32], window: usize) -> Vec<f64> {
+    if window == 0 || window > depths.len() {
+        return Vec::new();
+    }
+    let mut sum = depths[..window].iter().map(|&d| u64::from(d)).sum::<u64>();
+    let mut averages = Vec::with_capacity(depths.len() - window + 1);
+    #[allow(clippy::cast_precision_loss)]
+    let average = |sum: u64| sum as f64 / window as f64;
+    averages.push(average(sum));
+    for i in window..depths.len() {
+        sum = sum + u64::from(depths[i]) - u64::from(depths[i - window]);
+        averages.push(average(sum));
+    }
+    averages
+}
+
+impl fmt::Display for DepthReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const SHOWN: usize = 10;
+        let run = &self.longest_increasing_run;
+        writeln!(f, "{:<26}{}", "measurements", self.measurements)?;
+        writeln!(f, "{:<26}{}", "increases", self.increases)?;
+        writeln!(
+            f,
+            "{:<26}{} measurements from index {}",
+            "longest increasing run", run.len, run.start
+        )?;
+        match &self.largest_drop {
+            Some(d) => writeln!(
+                f,
+                "{:<26}{} at index {} ({} to {})",
+                "largest drop",
+                d.size(),
+                d.at,
+                d.from,
+                d.to
+            )?,
+            None => writeln!(f, "{:<26}none", "largest drop")?,
+        }
+        let mut positions = self
+            .decreases
+            .iter()
+            .take(SHOWN)
+            .map(usize::to_string)
+            .collect::<Vec<_>>();
+        if self.decreases.len() > SHOWN {
+            positions.push("...".to_owned());
+        }
+        writeln!(
+            f,
+            "{:<26}{} at {}",
+            "decreases",
+            self.decreases.len(),
+            if positions.is_empty() {
+                "none".to_owned()
+            } else {
+                positions.join(", ")
+            }
+        )?;
+        let label = format!("moving averages of {}", self.window);
+        if self.moving_averages.is_empty() {
+            writeln!(f, "{label:<26}none")
+        } else {
+            let min = self
+                .moving_averages
+                .iter()
+                .copied()
+                .fold(f64::MAX, f64::min);
+            let max = self
+                .moving_averages
+                .iter()
+                .copied()
+                .fold(f64::MIN, f64::max);
+            writeln!(
+                f,
+                "{:<26}{} from {:.1} to {:.1}",
+                label,
+                self.moving_averages.len(),
+                min,
+                max
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn lines(depths: &[u32]) -> String {
+        depths
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(depths in prop::collection::vec(0..10_000u32, 0..200)) {
+            let input = lines(&depths);
+            let increases = |xs: &[u32]| (1..xs.len()).filter(|&i| xs[i - 1] < xs[i]).count();
+            let sums = (2..depths.len())
+                .map(|i| depths[i - 2] + depths[i - 1] + depths[i])
+                .collect::<Vec<_>>();
+
+            prop_assert_eq!(part1(&input).unwrap(), increases(&depths));
+            prop_assert_eq!(part2(&input).unwrap(), increases(&sums));
+            prop_assert_eq!(Day01::stream_part1(&mut input.as_bytes()).unwrap(), increases(&depths));
+            prop_assert_eq!(Day01::stream_part2(&mut input.as_bytes()).unwrap(), increases(&sums));
+        }
+
+        #[test]
+        fn test_window_matches_naive(
+            depths in prop::collection::vec(0..10_000u32, 0..100),
+            window in 0..12usize,
+        ) {
+            let sums = (window..=depths.len())
+                .map(|i| depths[i - window..i].iter().sum::<u32>())
+                .collect::<Vec<_>>();
+            let expected = (1..sums.len()).filter(|&i| sums[i - 1] < sums[i]).count();
+            let input = lines(&depths);
+
+            prop_assert_eq!(count_increases(depths.iter().copied(), window), expected);
+            prop_assert_eq!(count_increases_str(&input, window).unwrap(), expected);
+            prop_assert_eq!(
+                parse_with_window(&input, window).unwrap().part2().unwrap(),
+                Answer::from(expected)
+            );
+            prop_assert_eq!(stream_count_increases(&mut input.as_bytes(), window).unwrap(), expected);
+        }
+    }
+
+    const EXAMPLE: [u32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn test_depth_report() {
+        let report = DepthReport::new(&EXAMPLE, 3);
+        assert_eq!(report.increases, 7);
+        assert_eq!(report.longest_increasing_run, Run { start: 0, len: 4 });
+        assert_eq!(
+            report.largest_drop,
+            Some(Decrease {
+                at: 4,
+                from: 210,
+                to: 200
+            })
+        );
+        assert_eq!(report.decreases, [4, 8]);
+        let sums = report
+            .moving_averages
+            .iter()
+            .map(|a| (a * 3.0).round())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sums,
+            [607.0, 618.0, 618.0, 617.0, 647.0, 716.0, 769.0, 792.0]
+        );
+        assert_eq!(
+            report.to_string(),
+            indoc::indoc! {"
+                measurements              10
+                increases                 7
+                longest increasing run    4 measurements from index 0
+                largest drop              10 at index 4 (210 to 200)
+                decreases                 2 at 4, 8
+                moving averages of 3      8 from 202.3 to 264.0
+            "}
+        );
+        let json = serde_json::from_str::<serde_json::Value>(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["largest_drop"]["at"], 4);
+        assert_eq!(json["decreases"], serde_json::json!([4, 8]));
+        assert_eq!(json["moving_averages"].as_array().unwrap().len(), 8);
+    }
+
+    #[test]
+    fn test_depth_report_empty() {
+        let report = DepthReport::new(&[], 3);
+        assert_eq!(report.longest_increasing_run, Run::default());
+        assert_eq!(report.largest_drop, None);
+        assert!(report.moving_averages.is_empty());
+        assert!(report
+            .to_string()
+            .contains("largest drop              none"));
+        assert_eq!(
+            DepthReport::new(&[5, 5, 6], 0).longest_increasing_run.len,
+            2
+        );
+    }
+
+    #[test]
+    fn test_window_wider_than_input() {
+        assert_eq!(count_increases([1, 2, 3], 3), 0);
+        assert_eq!(count_increases([1, 2, 3], usize::MAX), 0);
+        assert_eq!(count_increases([1, 2, 3, 4], 3), 1);
+    }
 }
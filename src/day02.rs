@@ -1,6 +1,12 @@
+use std::{fmt::Write as _, io::BufRead};
+
 use thiserror::Error;
 
-use super::Result;
+use super::{
+    overflow::Overflow,
+    parse::{self, ParseError},
+    Result, Solution, Streaming,
+};
 
 #[derive(Debug, Error)]
 enum ParseCommandError {
@@ -10,6 +16,9 @@ enum ParseCommandError {
     #[error("missing command value")]
     MissingValue,
 
+    #[error("`{0}` doesn't take a value")]
+    UnexpectedValue(String),
+
     #[error("failed to parse value")]
     ParseValue(#[source] std::num::ParseIntError),
 
@@ -17,67 +26,429 @@ enum ParseCommandError {
     UnknownCommand(String),
 }
 
-enum Command {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
     Forward(i32),
     Down(i32),
     Up(i32),
+    /// Moves backward, the opposite of `forward`.
+    Back(i32),
+    /// Turns around, `forward` and `back` move the other way after it.
+    Turn,
+    ResetAim,
 }
 
 impl std::str::FromStr for Command {
-    type Err = ParseCommandError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut pair = s.splitn(2, ' ');
-        let cmd = pair.next().ok_or(ParseCommandError::MissingName)?;
-        let val = pair.next().ok_or(ParseCommandError::MissingValue)?;
-        let val = val.parse::<i32>().map_err(ParseCommandError::ParseValue)?;
+        if s.is_empty() {
+            return Err(ParseError::new(s, s, ParseCommandError::MissingName));
+        }
+        let (cmd, val) = match s.split_once(' ') {
+            Some((cmd, val)) => (cmd, Some(val)),
+            None => (s, None),
+        };
+        let value = || {
+            let val = val.ok_or_else(|| ParseError::at_end(s, ParseCommandError::MissingValue))?;
+            val.parse::<i32>()
+                .map_err(|e| ParseError::new(s, val, ParseCommandError::ParseValue(e)))
+        };
+        let no_value = |command| match val {
+            Some(val) => Err(ParseError::new(
+                s,
+                val,
+                ParseCommandError::UnexpectedValue(cmd.to_owned()),
+            )),
+            None => Ok(command),
+        };
         match cmd {
-            "forward" => Ok(Self::Forward(val)),
-            "up" => Ok(Self::Up(val)),
-            "down" => Ok(Self::Down(val)),
-            _ => Err(ParseCommandError::UnknownCommand(cmd.to_owned())),
+            "forward" => Ok(Self::Forward(value()?)),
+            "up" => Ok(Self::Up(value()?)),
+            "down" => Ok(Self::Down(value()?)),
+            "back" => Ok(Self::Back(value()?)),
+            "turn" => no_value(Self::Turn),
+            "reset-aim" => no_value(Self::ResetAim),
+            _ => Err(ParseError::new(
+                s,
+                cmd,
+                ParseCommandError::UnknownCommand(cmd.to_owned()),
+            )),
         }
     }
 }
 
-fn parse_commands(input: &str) -> Result<Vec<Command>, ParseCommandError> {
-    input.lines().map(str::parse).collect()
+/// Parses a line, `None` if it's only a comment. Comments start with `#`.
+fn parse_line(line: &str) -> Result<Option<Command>, ParseError> {
+    match line.split_once('#') {
+        Some((code, _)) if code.trim().is_empty() => Ok(None),
+        Some((code, _)) => code.trim_end().parse().map(Some),
+        None => line.parse().map(Some),
+    }
 }
 
-/// # Errors
+fn parse_commands(input: &str) -> Result<Vec<Command>, ParseError> {
+    Ok(parse::lines(input, parse_line)?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Command>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_commands(input)?)
+    }
+
+    fn part1(commands: &Self::Input) -> Result<i32> {
+        Ok(follow(Naive, commands.iter().copied())?)
+    }
+
+    fn part2(commands: &Self::Input) -> Result<i32> {
+        Ok(follow(Aim, commands.iter().copied())?)
+    }
+}
+
+impl Streaming for Day02 {
+    fn stream_part1(input: &mut dyn BufRead) -> Result<i32> {
+        Ok(parse::until_error(
+            parse::read_lines(input, parse_line),
+            |c| follow(Naive, c.flatten()),
+        )??)
+    }
+
+    fn stream_part2(input: &mut dyn BufRead) -> Result<i32> {
+        Ok(parse::until_error(
+            parse::read_lines(input, parse_line),
+            |c| follow(Aim, c.flatten()),
+        )??)
+    }
+}
+
+const HORIZONTAL: Overflow = Overflow("horizontal position");
+const DEPTH: Overflow = Overflow("depth");
+const AIM: Overflow = Overflow("aim");
+const PRODUCT: Overflow = Overflow("horizontal position * depth");
+
+/// Position and aim of the submarine.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct State {
+    pub horizontal: i32,
+    pub depth: i32,
+    pub aim: i32,
+    /// Turned around, moving toward decreasing horizontal positions.
+    pub turned: bool,
+}
+
+impl State {
+    /// Moves `n` forward, or backward if `n` is negative, in the direction it's facing.
+    fn advance(&mut self, n: i32) -> Result<(), Overflow> {
+        let n = if self.turned {
+            n.checked_neg().ok_or(HORIZONTAL)?
+        } else {
+            n
+        };
+        self.horizontal = self.horizontal.checked_add(n).ok_or(HORIZONTAL)?;
+        Ok(())
+    }
+}
+
+/// How commands change the state of a [`Submarine`].
 ///
-/// Will return `Err` if the input contains an invalid command.
-pub fn part1(input: &str) -> Result<()> {
-    let mut h = 0;
-    let mut v = 0;
-    for cmd in parse_commands(input)? {
-        match cmd {
-            Command::Forward(n) => h += n,
-            Command::Down(n) => v += n,
-            Command::Up(n) => v -= n,
+/// `turn` and `reset-aim` do the same in every mode.
+pub trait Mode {
+    fn forward(&self, state: &mut State, n: i32) -> Result<(), Overflow>;
+
+    fn back(&self, state: &mut State, n: i32) -> Result<(), Overflow>;
+
+    fn down(&self, state: &mut State, n: i32) -> Result<(), Overflow>;
+
+    fn up(&self, state: &mut State, n: i32) -> Result<(), Overflow>;
+}
+
+/// Part 1: `down` and `up` change the depth, the aim is unused.
+#[derive(Clone, Copy, Debug)]
+pub struct Naive;
+
+impl Mode for Naive {
+    fn forward(&self, state: &mut State, n: i32) -> Result<(), Overflow> {
+        state.advance(n)
+    }
+
+    fn back(&self, state: &mut State, n: i32) -> Result<(), Overflow> {
+        state.advance(n.checked_neg().ok_or(HORIZONTAL)?)
+    }
+
+    fn down(&self, state: &mut State, n: i32) -> Result<(), Overflow> {
+        state.depth = state.depth.checked_add(n).ok_or(DEPTH)?;
+        Ok(())
+    }
+
+    fn up(&self, state: &mut State, n: i32) -> Result<(), Overflow> {
+        state.depth = state.depth.checked_sub(n).ok_or(DEPTH)?;
+        Ok(())
+    }
+}
+
+/// Part 2: `down` and `up` change the aim, moving changes the depth by the aim.
+#[derive(Clone, Copy, Debug)]
+pub struct Aim;
+
+impl Mode for Aim {
+    fn forward(&self, state: &mut State, n: i32) -> Result<(), Overflow> {
+        state.advance(n)?;
+        state.depth = state
+            .aim
+            .checked_mul(n)
+            .and_then(|d| state.depth.checked_add(d))
+            .ok_or(DEPTH)?;
+        Ok(())
+    }
+
+    fn back(&self, state: &mut State, n: i32) -> Result<(), Overflow> {
+        self.forward(state, n.checked_neg().ok_or(HORIZONTAL)?)
+    }
+
+    fn down(&self, state: &mut State, n: i32) -> Result<(), Overflow> {
+        state.aim = state.aim.checked_add(n).ok_or(AIM)?;
+        Ok(())
+    }
+
+    fn up(&self, state: &mut State, n: i32) -> Result<(), Overflow> {
+        state.aim = state.aim.checked_sub(n).ok_or(AIM)?;
+        Ok(())
+    }
+}
+
+/// Follows commands in a [`Mode`], optionally recording the state after each one.
+pub struct Submarine<M> {
+    mode: M,
+    state: State,
+    trace: Option<Vec<State>>,
+}
+
+impl<M: Mode> Submarine<M> {
+    pub fn new(mode: M) -> Self {
+        Self {
+            mode,
+            state: State::default(),
+            trace: None,
+        }
+    }
+
+    /// Submarine recording its state after each command, see [`Submarine::trace`].
+    pub fn with_trace(mode: M) -> Self {
+        Self {
+            trace: Some(Vec::new()),
+            ..Self::new(mode)
+        }
+    }
+
+    /// Follows `command`, leaving the state unchanged if it overflows.
+    pub fn execute(&mut self, command: Command) -> Result<(), Overflow> {
+        let mut state = self.state;
+        match command {
+            Command::Forward(n) => self.mode.forward(&mut state, n)?,
+            Command::Back(n) => self.mode.back(&mut state, n)?,
+            Command::Down(n) => self.mode.down(&mut state, n)?,
+            Command::Up(n) => self.mode.up(&mut state, n)?,
+            Command::Turn => state.turned = !state.turned,
+            Command::ResetAim => state.aim = 0,
         }
+        self.state = state;
+        if let Some(trace) = &mut self.trace {
+            trace.push(state);
+        }
+        Ok(())
+    }
+
+    pub fn run(&mut self, commands: impl IntoIterator<Item = Command>) -> Result<(), Overflow> {
+        commands.into_iter().try_for_each(|c| self.execute(c))
+    }
+
+    #[must_use]
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// States after each command, empty unless created with [`Submarine::with_trace`].
+    #[must_use]
+    pub fn trace(&self) -> &[State] {
+        self.trace.as_deref().unwrap_or_default()
     }
-    println!("{}", h * v);
-    Ok(())
+
+    /// The horizontal position multiplied by the depth, the answer of both parts.
+    pub fn product(&self) -> Result<i32, Overflow> {
+        self.state
+            .horizontal
+            .checked_mul(self.state.depth)
+            .ok_or(PRODUCT)
+    }
+}
+
+fn follow(mode: impl Mode, commands: impl IntoIterator<Item = Command>) -> Result<i32, Overflow> {
+    let mut submarine = Submarine::new(mode);
+    submarine.run(commands)?;
+    submarine.product()
+}
+
+/// Course of the submarine in both parts, to compare them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trajectories {
+    /// States at the start and after each command in part 1.
+    pub naive: Vec<State>,
+    /// The same in part 2.
+    pub aim: Vec<State>,
+}
+
+impl Trajectories {
+    pub fn new(commands: &[Command]) -> Result<Self, Overflow> {
+        fn trace(mode: impl Mode, commands: &[Command]) -> Result<Vec<State>, Overflow> {
+            let mut submarine = Submarine::with_trace(mode);
+            submarine.run(commands.iter().copied())?;
+            Ok(std::iter::once(State::default())
+                .chain(submarine.trace().iter().copied())
+                .collect())
+        }
+        Ok(Self {
+            naive: trace(Naive, commands)?,
+            aim: trace(Aim, commands)?,
+        })
+    }
+
+    fn parts(&self) -> [(&'static str, &[State]); 2] {
+        [("part1", &self.naive), ("part2", &self.aim)]
+    }
+
+    /// One row per part and step, with the columns `part,step,horizontal,depth,aim`.
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("part,step,horizontal,depth,aim\n");
+        for (part, states) in self.parts() {
+            for (step, s) in states.iter().enumerate() {
+                let _ = writeln!(
+                    out,
+                    "{},{},{},{},{}",
+                    part, step, s.horizontal, s.depth, s.aim
+                );
+            }
+        }
+        out
+    }
+}
+
+/// Parses `input` and follows it in both parts.
+///
+/// # Errors
+///
+/// Will return `Err` if the input contains an invalid command or the course overflows.
+pub fn trajectories(input: &str) -> Result<Trajectories> {
+    Ok(Trajectories::new(&parse_commands(input)?)?)
 }
 
 /// # Errors
 ///
 /// Will return `Err` if the input contains an invalid command.
-pub fn part2(input: &str) -> Result<()> {
-    let mut h = 0;
-    let mut v = 0;
-    let mut aim = 0;
-    for cmd in parse_commands(input)? {
-        match cmd {
-            Command::Forward(n) => {
-                h += n;
-                v += aim * n;
-            }
-            Command::Down(n) => aim += n,
-            Command::Up(n) => aim -= n,
+pub fn part1(input: &str) -> Result<i32> {
+    Day02::solve_part1(input)
+}
+
+/// # Errors
+///
+/// Will return `Err` if the input contains an invalid command.
+pub fn part2(input: &str) -> Result<i32> {
+    Day02::solve_part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse_commands("forward 5\ndown x\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 6, "down x"));
+    }
+
+    #[test]
+    fn test_new_commands() {
+        let input = "# comment\nforward 5\ndown 5  # aim\nturn\nback 2\nforward 4\nreset-aim\nturn\nforward 1\n";
+        let commands = parse_commands(input).unwrap();
+        assert_eq!(commands.len(), 8);
+        assert_eq!(commands[2], Command::Turn);
+
+        let mut naive = Submarine::with_trace(Naive);
+        naive.run(commands.iter().copied()).unwrap();
+        let horizontals = naive
+            .trace()
+            .iter()
+            .map(|s| s.horizontal)
+            .collect::<Vec<_>>();
+        assert_eq!(horizontals, [5, 5, 5, 7, 3, 3, 3, 4]);
+        assert_eq!(naive.product(), Ok(20));
+
+        let mut aim = Submarine::with_trace(Aim);
+        aim.run(commands.iter().copied()).unwrap();
+        let states = aim
+            .trace()
+            .iter()
+            .map(|s| (s.horizontal, s.depth, s.aim))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            states,
+            [
+                (5, 0, 0),
+                (5, 0, 5),
+                (5, 0, 5),
+                (7, -10, 5),
+                (3, 10, 5),
+                (3, 10, 0),
+                (3, 10, 0),
+                (4, 10, 0),
+            ]
+        );
+        assert!(Submarine::new(Aim).trace().is_empty());
+    }
+
+    #[test]
+    fn test_trajectories() {
+        let example = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+        let t = trajectories(example).unwrap();
+        let end = |states: &[State]| {
+            let s = states.last().unwrap();
+            (states.len(), s.horizontal, s.depth)
+        };
+        assert_eq!(end(&t.naive), (7, 15, 10));
+        assert_eq!(end(&t.aim), (7, 15, 60));
+
+        let csv = t.to_csv();
+        assert_eq!(csv.lines().count(), 1 + 7 * 2);
+        assert!(csv.starts_with("part,step,horizontal,depth,aim\npart1,0,0,0,0\npart1,1,5,0,0\n"));
+        assert!(csv.ends_with("part2,6,15,60,10\n"));
+
+        assert!(trajectories("forward 2147483647\nforward 1\n").is_err());
+    }
+
+    #[test]
+    fn test_command_errors() {
+        for (line, column) in [
+            ("turn 5", 6),
+            ("reset-aim x", 11),
+            ("back", 5),
+            ("left 1", 1),
+        ] {
+            let err = parse_commands(line).unwrap_err();
+            assert_eq!((err.line, err.column), (1, column), "{line}");
         }
+        // An overflowing command leaves the submarine where it was.
+        let mut submarine = Submarine::new(Naive);
+        submarine.execute(Command::Forward(i32::MAX)).unwrap();
+        assert_eq!(submarine.execute(Command::Forward(1)), Err(HORIZONTAL));
+        assert_eq!(submarine.state().horizontal, i32::MAX);
     }
-    println!("{}", h * v);
-    Ok(())
 }
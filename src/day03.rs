@@ -1,50 +1,266 @@
-use std::collections::BTreeMap;
+use std::fmt::{self, Write};
 
-use super::Result;
+use thiserror::Error;
 
-pub fn part1(input: &str) -> Result<()> {
-    let diagnostics = input.lines().collect::<Vec<_>>();
-    debug_assert!(!diagnostics.is_empty());
-    let width = diagnostics[0].len();
+use super::{
+    parse::{self, ParseError},
+    Result, Solution,
+};
 
-    let gamma = diagnostics
-        .iter()
-        .flat_map(|bits| bits.chars().enumerate())
-        .fold(BTreeMap::<usize, i32>::new(), |mut m, (i, c)| {
-            *m.entry(i).or_insert(0) += if c == '1' { 1 } else { -1 };
-            m
-        })
-        .into_values()
-        .fold(0, |g, v| (g << 1) + (if v < 0 { 0 } else { 1 }));
-    let mask: u32 = (1 << width) - 1;
-    let epsilon = !gamma & mask;
-    let n = gamma * epsilon;
-    println!("{}", n);
+#[derive(Debug, Error)]
+enum ParseReportError {
+    #[error("empty report")]
+    EmptyReport,
+
+    #[error("empty line")]
+    EmptyLine,
+
+    #[error("invalid bit {0:?}, expected `0` or `1`")]
+    InvalidBit(char),
+
+    #[error("found {0} bits instead of {1}")]
+    InvalidWidth(usize, usize),
+}
+
+/// Parses lines of bits with the same width.
+fn parse_report(input: &str) -> Result<Vec<Bits>, ParseError> {
+    let mut width = None;
+    let report = parse::lines(input, |s| {
+        if s.is_empty() {
+            return Err(ParseError::new(s, s, ParseReportError::EmptyLine));
+        }
+        if let Some((i, c)) = s.char_indices().find(|&(_, c)| c != '0' && c != '1') {
+            return Err(ParseError::new(s, &s[i..], ParseReportError::InvalidBit(c)));
+        }
+        let expected = *width.get_or_insert(s.len());
+        if s.len() != expected {
+            return Err(ParseError::new(
+                s,
+                s,
+                ParseReportError::InvalidWidth(s.len(), expected),
+            ));
+        }
+        Ok(Bits(s.bytes().map(|b| b == b'1').collect()))
+    })?;
+    if report.is_empty() {
+        return Err(ParseError::new(input, input, ParseReportError::EmptyReport));
+    }
+    Ok(report)
+}
+
+/// Binary number of any width, most significant bit first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bits(pub Vec<bool>);
+
+impl Bits {
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.0.len()
+    }
 
-    Ok(())
+    /// Flips every bit, keeping the width.
+    #[must_use]
+    pub fn complement(&self) -> Bits {
+        Bits(self.0.iter().map(|&b| !b).collect())
+    }
+
+    /// Digits in base 2^32, least significant first.
+    fn limbs(&self) -> Vec<u32> {
+        self.0
+            .rchunks(32)
+            .map(|chunk| chunk.iter().fold(0, |n, &b| n << 1 | u32::from(b)))
+            .collect()
+    }
+
+    /// `self * other` in decimal, which fits no integer type for wide reports.
+    #[must_use]
+    // Keeping the low 32 bits of each partial product, the rest is carried.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn product(&self, other: &Bits) -> String {
+        let (a, b) = (self.limbs(), other.limbs());
+        let mut limbs = vec![0u32; a.len() + b.len()];
+        for (i, &x) in a.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &y) in b.iter().enumerate() {
+                let t = u64::from(x) * u64::from(y) + u64::from(limbs[i + j]) + carry;
+                limbs[i + j] = t as u32;
+                carry = t >> 32;
+            }
+            limbs[i + b.len()] = carry as u32;
+        }
+        to_decimal(limbs)
+    }
+}
+
+impl fmt::Display for Bits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0
+            .iter()
+            .try_for_each(|&b| f.write_char(if b { '1' } else { '0' }))
+    }
+}
+
+// Divides by 10^9 until nothing is left, each remainder is 9 decimal digits.
+// The quotient of each limb fits in 32 bits because the remainder carried is less than 10^9.
+#[allow(clippy::cast_possible_truncation)]
+fn to_decimal(mut limbs: Vec<u32>) -> String {
+    const BASE: u64 = 1_000_000_000;
+    let mut chunks = Vec::new();
+    loop {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        if limbs.is_empty() {
+            break;
+        }
+        let mut rem = 0u64;
+        for limb in limbs.iter_mut().rev() {
+            let n = rem << 32 | u64::from(*limb);
+            *limb = (n / BASE) as u32;
+            rem = n % BASE;
+        }
+        chunks.push(rem);
+    }
+    let mut digits = chunks.pop().unwrap_or(0).to_string();
+    for chunk in chunks.iter().rev() {
+        let _ = write!(digits, "{chunk:09}");
+    }
+    digits
 }
 
-pub fn part2(input: &str) -> Result<()> {
-    let diagnostics = input.lines().collect::<Vec<_>>();
-    debug_assert!(!diagnostics.is_empty());
-    let width = diagnostics[0].len();
-    let mut oxy = diagnostics.clone();
-    let mut co2 = diagnostics;
-    for i in 0..width {
-        if oxy.len() > 1 {
-            let (a, b): (Vec<_>, Vec<_>) = oxy.into_iter().partition(|s| s.as_bytes()[i] == b'1');
-            oxy = if a.len() >= b.len() { a } else { b };
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Vec<Bits>;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_report(input)?)
+    }
+
+    fn part1(diagnostics: &Self::Input) -> Result<String> {
+        let width = diagnostics[0].width();
+        let mut ones = vec![0usize; width];
+        for bits in diagnostics {
+            for (count, &b) in ones.iter_mut().zip(&bits.0) {
+                *count += usize::from(b);
+            }
         }
-        if co2.len() > 1 {
-            let (a, b): (Vec<_>, Vec<_>) = co2.into_iter().partition(|s| s.as_bytes()[i] == b'0');
-            co2 = if a.len() <= b.len() { a } else { b };
+        let gamma = Bits(ones.iter().map(|&n| 2 * n >= diagnostics.len()).collect());
+        let epsilon = gamma.complement();
+        Ok(gamma.product(&epsilon))
+    }
+
+    fn part2(diagnostics: &Self::Input) -> Result<String> {
+        let width = diagnostics[0].width();
+        let mut oxy = diagnostics.iter().collect::<Vec<_>>();
+        let mut co2 = oxy.clone();
+        for i in 0..width {
+            if oxy.len() > 1 {
+                let (a, b): (Vec<_>, Vec<_>) = oxy.into_iter().partition(|bits| bits.0[i]);
+                oxy = if a.len() >= b.len() { a } else { b };
+            }
+            if co2.len() > 1 {
+                let (a, b): (Vec<_>, Vec<_>) = co2.into_iter().partition(|bits| !bits.0[i]);
+                // Keep all if they have the same bit, instead of none.
+                co2 = if !a.is_empty() && (a.len() <= b.len() || b.is_empty()) {
+                    a
+                } else {
+                    b
+                };
+            }
         }
+        Ok(oxy[0].product(co2[0]))
     }
+}
+
+pub fn part1(input: &str) -> Result<String> {
+    Day03::solve_part1(input)
+}
+
+pub fn part2(input: &str) -> Result<String> {
+    Day03::solve_part2(input)
+}
 
-    let oxy = u32::from_str_radix(oxy[0], 2)?;
-    let co2 = u32::from_str_radix(co2[0], 2)?;
-    let n = oxy * co2;
-    println!("{}", n);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
 
-    Ok(())
+    // Filters one bit at a time, keeping all numbers if they have the same bit.
+    fn rating(mut nums: Vec<u32>, width: usize, most_common: bool) -> u32 {
+        for i in (0..width).rev() {
+            let ones = nums.iter().filter(|&&n| n >> i & 1 == 1).count();
+            let zeros = nums.len() - ones;
+            if nums.len() == 1 || ones == 0 || zeros == 0 {
+                continue;
+            }
+            let keep = if most_common {
+                ones >= zeros
+            } else {
+                ones < zeros
+            };
+            nums.retain(|&n| (n >> i & 1 == 1) == keep);
+        }
+        nums[0]
+    }
+
+    fn report() -> impl Strategy<Value = (usize, Vec<u32>)> {
+        (1..=12usize).prop_flat_map(|width| {
+            let nums = prop::collection::vec(0..1u32 << width, 1..50);
+            (Just(width), nums)
+        })
+    }
+
+    #[test]
+    fn test_product() {
+        let bits = |s: &str| Bits(s.bytes().map(|b| b == b'1').collect());
+        let ones = bits(&"1".repeat(128));
+        assert_eq!(ones.product(&bits("1")), u128::MAX.to_string());
+        // (2^128 - 1)^2 = 2^256 - 2^129 + 1
+        assert_eq!(
+            ones.product(&ones),
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+        );
+        assert_eq!(ones.product(&ones.complement()), "0");
+        assert_eq!(bits("0101").to_string(), "0101");
+        assert_eq!(
+            bits("1000000000000000000000000000000000000000").product(&bits("11")),
+            "1649267441664"
+        );
+    }
+
+    #[test]
+    fn test_wide_report() {
+        // Gamma is the first line and epsilon the second, both 100 bits.
+        let gamma = "1".repeat(60) + &"0".repeat(40);
+        let epsilon = "0".repeat(60) + &"1".repeat(40);
+        let input = format!("{gamma}\n{gamma}\n{epsilon}\n");
+        // (2^100 - 2^40) * (2^40 - 1), too large for u128
+        let expected = "1393796574906896294536828343025496227840000";
+        assert_eq!(part1(&input).unwrap(), expected);
+        assert_eq!(part2(&input).unwrap(), expected);
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive((width, nums) in report()) {
+            let input = nums
+                .iter()
+                .map(|n| format!("{n:0width$b}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let gamma = (0..width)
+                .filter(|&i| 2 * nums.iter().filter(|&&n| n >> i & 1 == 1).count() >= nums.len())
+                .fold(0, |g, i| g | 1 << i);
+            let epsilon = !gamma & ((1 << width) - 1);
+
+            prop_assert_eq!(part1(&input).unwrap(), (gamma * epsilon).to_string());
+            prop_assert_eq!(
+                part2(&input).unwrap(),
+                (rating(nums.clone(), width, true) * rating(nums, width, false)).to_string()
+            );
+        }
+    }
 }
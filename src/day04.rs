@@ -2,7 +2,7 @@ use std::{collections::HashSet, num::ParseIntError};
 
 use thiserror::Error;
 
-use super::Result;
+use super::{parse::ParseError, Result, Solution};
 
 #[derive(Debug, Error)]
 enum Error {
@@ -12,11 +12,8 @@ enum Error {
     #[error("missing boards")]
     MissingBoards,
 
-    #[error("failed to parse drawn numbers")]
-    ParseDraws(#[source] ParseIntError),
-
-    #[error("failed to parse boards")]
-    ParseBoard(#[source] ParseBoardError),
+    #[error("failed to parse drawn number")]
+    ParseDraw(#[source] ParseIntError),
 }
 
 #[derive(Debug, Error)]
@@ -34,17 +31,17 @@ enum ParseBoardError {
     DuplicateNumber(u32),
 }
 
-#[derive(Debug, Default)]
-struct Board {
+#[derive(Clone, Debug, Default)]
+pub struct Board {
     nums: [[u32; 5]; 5],
     marked: [[bool; 5]; 5],
     // Frequencies of marked rows and columns.
     // Bingo if any of them are 5, i.e, any row/column marked 5 times.
     rows: [u8; 5],
     cols: [u8; 5],
-    // Sum of unmarked numbers
-    sum: u32,
-    score: Option<u32>,
+    // Sum of unmarked numbers, and the score, wide enough for any `u32` numbers.
+    sum: u64,
+    score: Option<u128>,
 }
 
 impl Board {
@@ -55,7 +52,7 @@ impl Board {
     /// Returns the score if `n` makes the board win.
     /// If the board is already completed, the recorded score is returned.
     /// Returns `None` otherwise.
-    fn mark_number(&mut self, n: u32) -> Option<u32> {
+    fn mark_number(&mut self, n: u32) -> Option<u128> {
         if self.has_won() {
             return self.score;
         }
@@ -67,9 +64,9 @@ impl Board {
                     self.marked[i][j] = true;
                     self.rows[i] += 1;
                     self.cols[j] += 1;
-                    self.sum -= n;
+                    self.sum -= u64::from(n);
                     if self.rows[i] == 5 || self.cols[j] == 5 {
-                        self.score = Some(n * self.sum);
+                        self.score = Some(u128::from(n) * u128::from(self.sum));
                         return self.score;
                     }
                 }
@@ -81,12 +78,16 @@ impl Board {
 }
 
 impl std::str::FromStr for Board {
-    type Err = ParseBoardError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let rows = s.split('\n').collect::<Vec<_>>();
         if rows.len() != 5 {
-            return Err(ParseBoardError::InvalidRowCount(rows.len()));
+            return Err(ParseError::new(
+                s,
+                s,
+                ParseBoardError::InvalidRowCount(rows.len()),
+            ));
         }
 
         let mut nums = [[0; 5]; 5];
@@ -96,15 +97,25 @@ impl std::str::FromStr for Board {
             let cols = row.split_ascii_whitespace();
             let count = cols.clone().count();
             if count != 5 {
-                return Err(ParseBoardError::InvalidColCount(i, count));
+                return Err(ParseError::new(
+                    s,
+                    row,
+                    ParseBoardError::InvalidColCount(i, count),
+                ));
             }
 
             for (j, col) in cols.enumerate() {
-                let val = col.parse().map_err(ParseBoardError::ParseNumber)?;
+                let val = col
+                    .parse()
+                    .map_err(|e| ParseError::new(s, col, ParseBoardError::ParseNumber(e)))?;
                 if !seen.insert(val) {
-                    return Err(ParseBoardError::DuplicateNumber(val));
+                    return Err(ParseError::new(
+                        s,
+                        col,
+                        ParseBoardError::DuplicateNumber(val),
+                    ));
                 }
-                sum += val;
+                sum += u64::from(val);
                 nums[i][j] = val;
             }
         }
@@ -117,94 +128,81 @@ impl std::str::FromStr for Board {
     }
 }
 
-fn parse_draws_and_boards(input: &str) -> Result<(Vec<u32>, Vec<Board>), Error> {
+fn parse_draws_and_boards(input: &str) -> Result<(Vec<u32>, Vec<Board>), ParseError> {
     let mut inputs = input.trim_end().split("\n\n");
-    let draws = inputs
-        .next()
-        .unwrap_or_default()
-        .split(',')
-        .map(str::parse)
-        .collect::<Result<Vec<u32>, ParseIntError>>()
-        .map_err(Error::ParseDraws)?;
+    let draws = inputs.next().unwrap_or_default();
     if draws.is_empty() {
-        return Err(Error::MissingDraws);
+        return Err(ParseError::new(input, draws, Error::MissingDraws));
     }
+    let draws = draws
+        .split(',')
+        .map(|s| {
+            s.parse()
+                .map_err(|e| ParseError::new(input, s, Error::ParseDraw(e)))
+        })
+        .collect::<Result<Vec<u32>, _>>()?;
 
     let boards = inputs
-        .map(str::parse)
-        .collect::<Result<Vec<Board>, ParseBoardError>>()
-        .map_err(Error::ParseBoard)?;
+        .map(|s| s.parse().map_err(|e: ParseError| e.within(input, s)))
+        .collect::<Result<Vec<Board>, _>>()?;
     if boards.is_empty() {
-        return Err(Error::MissingBoards);
+        return Err(ParseError::at_end(input, Error::MissingBoards));
     }
     Ok((draws, boards))
 }
 
-pub fn part1(input: &str) -> Result<String> {
-    let (draws, mut boards) = parse_draws_and_boards(input)?;
-    for n in draws {
-        for board in &mut boards {
-            if let Some(score) = board.mark_number(n) {
-                return Ok(format!("{}", score));
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = (Vec<u32>, Vec<Board>);
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_draws_and_boards(input)?)
+    }
+
+    fn part1((draws, boards): &Self::Input) -> Result<String> {
+        let mut boards = boards.clone();
+        for &n in draws {
+            for board in &mut boards {
+                if let Some(score) = board.mark_number(n) {
+                    return Ok(format!("{score}"));
+                }
             }
         }
+        Ok("no winner".to_owned())
     }
-    Ok("no winner".to_owned())
-}
 
-pub fn part2(input: &str) -> Result<String> {
-    let (draws, mut boards) = parse_draws_and_boards(input)?;
-    let mut remaining = boards.len();
-    let mut last_score = None;
-    for n in draws {
-        for board in &mut boards {
-            if !board.has_won() {
-                if let Some(score) = board.mark_number(n) {
-                    last_score = Some(score);
-                    remaining -= 1;
+    fn part2((draws, boards): &Self::Input) -> Result<String> {
+        let mut boards = boards.clone();
+        let mut remaining = boards.len();
+        let mut last_score = None;
+        for &n in draws {
+            for board in &mut boards {
+                if !board.has_won() {
+                    if let Some(score) = board.mark_number(n) {
+                        last_score = Some(score);
+                        remaining -= 1;
+                    }
                 }
             }
+            if remaining == 0 {
+                break;
+            }
         }
-        if remaining == 0 {
-            break;
+        if let Some(score) = last_score {
+            Ok(format!("{score}"))
+        } else {
+            Ok("no winner".to_owned())
         }
     }
-    if let Some(score) = last_score {
-        Ok(format!("{}", score))
-    } else {
-        Ok("no winner".to_owned())
-    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use indoc::indoc;
-
-    #[test]
-    fn test_example() {
-        let input = indoc! {"
-            7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
-
-            22 13 17 11  0
-             8  2 23  4 24
-            21  9 14 16  7
-             6 10  3 18  5
-             1 12 20 15 19
-
-             3 15  0  2 22
-             9 18 13 17  5
-            19  8  7 25 23
-            20 11 10 24  4
-            14 21 16 12  6
-
-            14 21 17 24  4
-            10 16 15  9 19
-            18  8 23 26 20
-            22 11 13  6  5
-             2  0 12  3  7
-        "};
-        assert_eq!(part1(input).unwrap(), "4512");
-        assert_eq!(part2(input).unwrap(), "1924");
-    }
+pub fn part1(input: &str) -> Result<String> {
+    Day04::solve_part1(input)
+}
+
+pub fn part2(input: &str) -> Result<String> {
+    Day04::solve_part2(input)
 }
@@ -1,8 +1,14 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, io::BufRead};
 
 use thiserror::Error;
 
-use super::Result;
+use super::{
+    grid::{Grid, Pos},
+    image_export::{self, Export},
+    parse::{self, ParseError},
+    visualize::{self, Frame, Renderer, Rgb, Visualize},
+    Result, Solution, Streaming,
+};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 struct Point {
@@ -23,18 +29,33 @@ enum ParsePointError {
 }
 
 impl std::str::FromStr for Point {
-    type Err = ParsePointError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, y) = s.split_once(',').ok_or(ParsePointError::MissingComma)?;
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| ParseError::new(s, s, ParsePointError::MissingComma))?;
+        // Coordinates are limited to `u16` so that a segment has at most 65536 points,
+        // and moving along it can't overflow.
+        let x: u16 = x
+            .parse()
+            .map_err(|e| ParseError::new(s, x, ParsePointError::ParseX(e)))?;
+        let y: u16 = y
+            .parse()
+            .map_err(|e| ParseError::new(s, y, ParsePointError::ParseY(e)))?;
         Ok(Point {
-            x: x.parse().map_err(ParsePointError::ParseX)?,
-            y: y.parse().map_err(ParsePointError::ParseY)?,
+            x: x.into(),
+            y: y.into(),
         })
     }
 }
 
 impl Point {
+    fn pos(self) -> Pos {
+        let coord = |v: i32| usize::try_from(v).expect("coordinates are u16");
+        (coord(self.y), coord(self.x))
+    }
+
     fn move_by(self, dx: i32, dy: i32) -> Point {
         Point {
             x: self.x + dx,
@@ -44,45 +65,40 @@ impl Point {
 }
 
 #[derive(Copy, Clone, Debug)]
-struct LineSegment((Point, Point));
+pub struct LineSegment((Point, Point));
 
 #[derive(Debug, Error)]
 enum ParseLineSegmentError {
     #[error("missing point separator arrow `->`")]
     MissingArrow,
 
-    #[error("failed to parse the first point")]
-    ParsePointA(#[source] ParsePointError),
-
-    #[error("failed to parse the second point")]
-    ParsePointB(#[source] ParsePointError),
-
     #[error("invalid segment: must be horizontal, vertical or 45 degrees diagnoal")]
     InvalidSegment,
 }
 
 impl std::str::FromStr for LineSegment {
-    type Err = ParseLineSegmentError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (a, b) = s
             .split_once(" -> ")
-            .ok_or(ParseLineSegmentError::MissingArrow)?;
-        let p1: Point = a.parse().map_err(ParseLineSegmentError::ParsePointA)?;
-        let p2: Point = b.parse().map_err(ParseLineSegmentError::ParsePointB)?;
+            .ok_or_else(|| ParseError::new(s, s, ParseLineSegmentError::MissingArrow))?;
+        let p1: Point = a.parse().map_err(|e: ParseError| e.within(s, a))?;
+        let p2: Point = b.parse().map_err(|e: ParseError| e.within(s, b))?;
         let (dx, dy) = (p2.x - p1.x, p2.y - p1.y);
         if dx == 0 || dy == 0 || dx.abs() == dy.abs() {
             Ok(LineSegment((p1, p2)))
         } else {
-            Err(ParseLineSegmentError::InvalidSegment)
+            Err(ParseError::new(s, s, ParseLineSegmentError::InvalidSegment))
         }
     }
 }
 
 impl LineSegment {
+    // A single point is horizontal and vertical, not diagonal.
     fn is_diagonal(&self) -> bool {
         let LineSegment((p1, p2)) = self;
-        (p2.x - p1.x).abs() == (p2.y - p1.y).abs()
+        p1.x != p2.x && p1.y != p2.y
     }
 
     fn into_points(self) -> impl Iterator<Item = Point> {
@@ -92,8 +108,8 @@ impl LineSegment {
     }
 }
 
-fn parse_line_segments(input: &str) -> Result<Vec<LineSegment>, ParseLineSegmentError> {
-    input.lines().map(str::parse).collect()
+fn parse_line_segments(input: &str) -> Result<Vec<LineSegment>, ParseError> {
+    parse::lines(input, str::parse)
 }
 
 fn count_overlaps(iter: impl Iterator<Item = Point>, n: usize) -> usize {
@@ -106,41 +122,187 @@ fn count_overlaps(iter: impl Iterator<Item = Point>, n: usize) -> usize {
     .count()
 }
 
-pub fn part1(input: &str) -> Result<usize> {
-    let points = parse_line_segments(input)?
-        .into_iter()
-        .filter(|x| !x.is_diagonal())
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Vec<LineSegment>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_line_segments(input)?)
+    }
+
+    fn part1(segments: &Self::Input) -> Result<usize> {
+        Ok(overlaps(segments.iter().copied(), false))
+    }
+
+    fn part2(segments: &Self::Input) -> Result<usize> {
+        Ok(overlaps(segments.iter().copied(), true))
+    }
+}
+
+// Only the counts of the points covered so far are kept, so the memory is
+// bounded by the area covered instead of the number of segments.
+impl Streaming for Day05 {
+    fn stream_part1(input: &mut dyn BufRead) -> Result<usize> {
+        parse::until_error(parse::read_lines(input, str::parse), |segments| {
+            overlaps(segments, false)
+        })
+    }
+
+    fn stream_part2(input: &mut dyn BufRead) -> Result<usize> {
+        parse::until_error(parse::read_lines(input, str::parse), |segments| {
+            overlaps(segments, true)
+        })
+    }
+}
+
+/// Number of points covered by at least two segments.
+fn overlaps(segments: impl Iterator<Item = LineSegment>, diagonals: bool) -> usize {
+    let points = segments
+        .filter(|x| diagonals || !x.is_diagonal())
         .flat_map(LineSegment::into_points);
-    Ok(count_overlaps(points, 2))
+    count_overlaps(points, 2)
+}
+
+/// Draws the segments one at a time, including diagonals, showing where they overlap.
+struct Vents {
+    segments: Vec<LineSegment>,
+    counts: Grid<u32>,
+    drawn: usize,
+    overlaps: usize,
+}
+
+impl Vents {
+    fn new(segments: Vec<LineSegment>) -> Result<Self> {
+        let (height, width) = segments
+            .iter()
+            .flat_map(|&LineSegment((p1, p2))| [p1.pos(), p2.pos()])
+            .fold((0, 0), |(h, w), (i, j)| (h.max(i + 1), w.max(j + 1)));
+        visualize::check_size(width, height)?;
+        Ok(Vents {
+            segments,
+            counts: Grid::from_fn(width, height, |_| 0),
+            drawn: 0,
+            overlaps: 0,
+        })
+    }
+}
+
+impl Visualize for Day05 {
+    fn renderer(segments: Self::Input) -> Result<Box<dyn Renderer>> {
+        Ok(Box::new(Vents::new(segments)?))
+    }
+}
+
+/// Heat map of the number of segments at each point, including diagonals.
+impl Export for Day05 {
+    fn image(segments: Self::Input) -> Result<Grid<Rgb>> {
+        let mut vents = Vents::new(segments)?;
+        while vents.step() {}
+        let max = vents.counts.iter().copied().max().unwrap_or_default();
+        Ok(vents.counts.map(|&c| match c {
+            0 => Rgb(10, 10, 30),
+            _ => image_export::heat(image_export::fraction(c, 1, max)),
+        }))
+    }
+}
+
+impl Renderer for Vents {
+    fn frame(&self) -> Frame {
+        Frame {
+            pixels: self.counts.map(|&c| match c {
+                0 => Rgb(10, 10, 30),
+                1 => Rgb(40, 90, 170),
+                2 => Rgb(250, 170, 40),
+                _ => Rgb(255, 60, 30),
+            }),
+            caption: format!(
+                "segment {}/{}, lines overlap at {} points",
+                self.drawn,
+                self.segments.len(),
+                self.overlaps
+            ),
+        }
+    }
+
+    fn step(&mut self) -> bool {
+        let Some(&segment) = self.segments.get(self.drawn) else {
+            return false;
+        };
+        for p in segment.into_points() {
+            let count = &mut self.counts[p.pos()];
+            *count += 1;
+            if *count == 2 {
+                self.overlaps += 1;
+            }
+        }
+        self.drawn += 1;
+        true
+    }
+}
+
+pub fn part1(input: &str) -> Result<usize> {
+    Day05::solve_part1(input)
 }
 
 pub fn part2(input: &str) -> Result<usize> {
-    let points = parse_line_segments(input)?
-        .into_iter()
-        .flat_map(LineSegment::into_points);
-    Ok(count_overlaps(points, 2))
+    Day05::solve_part2(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use indoc::indoc;
-
-    #[test]
-    fn test_example() {
-        let input = indoc! {"
-            0,9 -> 5,9
-            8,0 -> 0,8
-            9,4 -> 3,4
-            2,2 -> 2,1
-            7,0 -> 7,4
-            6,4 -> 2,0
-            0,9 -> 2,9
-            3,4 -> 1,4
-            0,0 -> 8,8
-            5,5 -> 8,2
-        "};
-        assert_eq!(part1(input).unwrap(), 5);
-        assert_eq!(part2(input).unwrap(), 12);
+    use proptest::prelude::*;
+
+    const SIZE: i32 = 20;
+
+    // Segments in a small area, projected to be horizontal, vertical or diagonal.
+    fn segment() -> impl Strategy<Value = (i32, i32, i32, i32)> {
+        let coord = || 0..SIZE;
+        (coord(), coord(), coord(), coord(), 0..3).prop_map(|(x1, y1, x2, y2, kind)| match kind {
+            0 => (x1, y1, x2, y1),
+            1 => (x1, y1, x1, y2),
+            _ => {
+                let d = (x2 - x1).abs().min((y2 - y1).abs());
+                (
+                    x1,
+                    y1,
+                    x1 + d * (x2 - x1).signum(),
+                    y1 + d * (y2 - y1).signum(),
+                )
+            }
+        })
+    }
+
+    fn count_naive(segments: &[(i32, i32, i32, i32)], diagonals: bool) -> usize {
+        let mut counts = [[0; SIZE as usize]; SIZE as usize];
+        for &(x1, y1, x2, y2) in segments {
+            if x1 != x2 && y1 != y2 && !diagonals {
+                continue;
+            }
+            let len = (x2 - x1).abs().max((y2 - y1).abs());
+            for t in 0..=len {
+                let x = x1 + t * (x2 - x1).signum();
+                let y = y1 + t * (y2 - y1).signum();
+                counts[usize::try_from(y).unwrap()][usize::try_from(x).unwrap()] += 1;
+            }
+        }
+        counts.iter().flatten().filter(|&&c| c >= 2).count()
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(segments in prop::collection::vec(segment(), 0..30)) {
+            let input = segments
+                .iter()
+                .map(|(x1, y1, x2, y2)| format!("{x1},{y1} -> {x2},{y2}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            prop_assert_eq!(part1(&input).unwrap(), count_naive(&segments, false));
+            prop_assert_eq!(part2(&input).unwrap(), count_naive(&segments, true));
+        }
     }
 }
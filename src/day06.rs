@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use super::Result;
+use super::{overflow::Overflow, parse::ParseError, Result, Solution};
 
 #[derive(Debug, Error)]
 enum Error {
@@ -11,14 +11,16 @@ enum Error {
     InvalidTimer(u8),
 }
 
-fn parse_initial_state(input: &str) -> Result<[usize; 9], Error> {
+fn parse_initial_state(input: &str) -> Result<[usize; 9], ParseError> {
     let vec = input
         .trim_end()
         .split(',')
         .map(|s| {
-            let t = s.parse().map_err(Error::ParseTimer)?;
+            let t = s
+                .parse()
+                .map_err(|e| ParseError::new(input, s, Error::ParseTimer(e)))?;
             if t > 8 {
-                Err(Error::InvalidTimer(t))
+                Err(ParseError::new(input, s, Error::InvalidTimer(t)))
             } else {
                 Ok(t)
             }
@@ -37,71 +39,98 @@ fn parse_initial_state(input: &str) -> Result<[usize; 9], Error> {
 // [1, 2, 1, 0, 0, 0, 1, 0, 1] 1, 2, 1, 6, 0, 8
 // [2, 1, 0, 0, 0, 1, 1, 1, 1] 0, 1, 0, 5, 6, 7, 8
 // [1, 0, 0, 0, 1, 1, 3, 1, 2] 6, 0, 6, 4, 5, 6, 7, 8, 8
-fn simulate_days(mut state: [usize; 9], days: usize) -> [usize; 9] {
+fn simulate_days(mut state: [usize; 9], days: usize) -> Result<usize, Overflow> {
     for _ in 0..days {
         // current 0 to 6 and 8
         state.rotate_left(1);
-        state[6] += state[8];
+        state[6] = state[6].checked_add(state[8]).ok_or(COUNT)?;
     }
     state
+        .iter()
+        .try_fold(0usize, |sum, &n| sum.checked_add(n))
+        .ok_or(COUNT)
+}
+
+const COUNT: Overflow = Overflow("number of lanternfish");
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = [usize; 9];
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_initial_state(input)?)
+    }
+
+    fn part1(state: &Self::Input) -> Result<usize> {
+        Ok(simulate_days(*state, 80)?)
+    }
+
+    fn part2(state: &Self::Input) -> Result<usize> {
+        Ok(simulate_days(*state, 256)?)
+    }
 }
 
 pub fn part1(input: &str) -> Result<usize> {
-    let state = parse_initial_state(input)?;
-    Ok(simulate_days(state, 80).iter().sum())
-    // Ok(count_after_days(state, 80))
+    Day06::solve_part1(input)
 }
 
 pub fn part2(input: &str) -> Result<usize> {
-    let state = parse_initial_state(input)?;
-    Ok(simulate_days(state, 256).iter().sum())
-    // Ok(count_after_days(state, 256))
+    Day06::solve_part2(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use indoc::indoc;
-
-    #[test]
-    fn test_example() {
-        let input = indoc! {"
-            3,4,3,1,2
-        "};
-        assert_eq!(part1(input).unwrap(), 5934);
-        assert_eq!(part2(input).unwrap(), 26_984_457_539);
+    use proptest::prelude::*;
+
+    // Original solution that counts the number of fish recursively with memoization
+    fn count_after_days(state: [usize; 9], days: usize) -> usize {
+        state
+            .into_iter()
+            .enumerate()
+            .fold(0, |s, (t, c)| s + c * count0(days.saturating_sub(t)))
     }
-}
 
-// Original solution that counts the number of fish recursively with memoization
-#[allow(dead_code)]
-fn count_after_days(state: [usize; 9], days: usize) -> usize {
-    state
-        .into_iter()
-        .enumerate()
-        .fold(0, |s, (t, c)| s + c * count0(days.saturating_sub(t)))
-}
+    // The number of lanternfish after `days` starting from one with timer `0`.
+    fn count0(days: usize) -> usize {
+        use once_cell::sync::Lazy;
+        use std::{collections::BTreeMap, sync::Mutex};
+        static MEMO: Lazy<Mutex<BTreeMap<usize, usize>>> =
+            Lazy::new(|| Mutex::new(BTreeMap::new()));
 
-#[allow(dead_code)]
-// The number of lanternfish after `days` starting from one with timer `0`.
-fn count0(days: usize) -> usize {
-    use once_cell::sync::Lazy;
-    use std::{collections::BTreeMap, sync::Mutex};
-    static MEMO: Lazy<Mutex<BTreeMap<usize, usize>>> = Lazy::new(|| Mutex::new(BTreeMap::new()));
-
-    fn inner(days: usize) -> usize {
-        if days == 0 {
-            1
-        } else {
-            count0(days.saturating_sub(7)) + count0(days.saturating_sub(9))
+        fn inner(days: usize) -> usize {
+            if days == 0 {
+                1
+            } else {
+                count0(days.saturating_sub(7)) + count0(days.saturating_sub(9))
+            }
+        }
+
+        if let Some(&cached) = MEMO.lock().unwrap().get(&days) {
+            return cached;
         }
-    }
 
-    if let Some(&cached) = MEMO.lock().unwrap().get(&days) {
-        return cached;
+        let res = inner(days);
+        MEMO.lock().unwrap().insert(days, res);
+        res
     }
 
-    let res = inner(days);
-    MEMO.lock().unwrap().insert(days, res);
-    res
+    proptest! {
+        #[test]
+        fn test_matches_memoized(timers in prop::collection::vec(0..=8usize, 0..50), days in 0..=256usize) {
+            let mut state = [0; 9];
+            for &t in &timers {
+                state[t] += 1;
+            }
+            prop_assert_eq!(simulate_days(state, days).unwrap(), count_after_days(state, days));
+
+            let input = timers.iter().map(usize::to_string).collect::<Vec<_>>().join(",");
+            if !input.is_empty() {
+                prop_assert_eq!(part2(&input).unwrap(), count_after_days(state, 256));
+            }
+        }
+    }
 }
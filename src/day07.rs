@@ -1,46 +1,114 @@
-use super::Result;
+use thiserror::Error;
 
-pub fn part1(input: &str) -> Result<u32> {
-    Ok(min_cost_by(&parse_input(input)?, |x| x))
+use super::{overflow::Overflow, par, parse::ParseError, Result, Solution};
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("failed to parse position")]
+    ParsePosition(#[source] std::num::ParseIntError),
+
+    #[error("no positions to align")]
+    NoPositions,
 }
 
-pub fn part2(input: &str) -> Result<u32> {
-    Ok(min_cost_by(&parse_input(input)?, |x| x * (x + 1) / 2))
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Vec<u32>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(positions: &Self::Input) -> Result<u64> {
+        min_cost_by(positions, |x| x)
+    }
+
+    fn part2(positions: &Self::Input) -> Result<u64> {
+        min_cost_by(positions, |x| x * (x + 1) / 2)
+    }
 }
 
-fn parse_input(input: &str) -> Result<Vec<u32>, std::num::ParseIntError> {
+pub fn part1(input: &str) -> Result<u64> {
+    Day07::solve_part1(input)
+}
+
+pub fn part2(input: &str) -> Result<u64> {
+    Day07::solve_part2(input)
+}
+
+fn parse_input(input: &str) -> Result<Vec<u32>, ParseError> {
     input
         .trim_end()
         .split(',')
-        .map(str::parse)
+        .map(|s| {
+            s.parse()
+                .map_err(|e| ParseError::new(input, s, Error::ParsePosition(e)))
+        })
         .collect::<Result<Vec<u32>, _>>()
 }
 
-fn min_cost_by(positions: &[u32], cost_fn: impl Fn(u32) -> u32) -> u32 {
-    let max = *positions.iter().max().expect("nonempty input");
-    (0..=max)
-        .map(|q| {
-            positions
-                .iter()
-                .copied()
-                .map(|p| cost_fn(if q < p { p - q } else { q - p }))
-                .sum()
+// The total cost is convex in the target position, so the cheapest one is found by
+// binary searching for where it stops decreasing, instead of trying every position.
+// The cost of a crab fits in `u64` and the total in `u128`, only the answer can overflow.
+fn min_cost_by(positions: &[u32], cost_fn: impl Fn(u64) -> u64 + Sync + Send) -> Result<u64> {
+    let total = |q: u32| -> u128 {
+        par::sum(positions, |&p| {
+            u128::from(cost_fn(u64::from(p.abs_diff(q))))
         })
-        .min()
-        .expect("nonempty input")
+    };
+    let mut lo = *positions.iter().min().ok_or(Error::NoPositions)?;
+    let mut hi = *positions.iter().max().ok_or(Error::NoPositions)?;
+    // The last probe computes the total at where the search ends.
+    let mut min = None;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let (at_mid, after) = (total(mid), total(mid + 1));
+        if at_mid <= after {
+            hi = mid;
+            min = Some(at_mid);
+        } else {
+            lo = mid + 1;
+            min = Some(after);
+        }
+    }
+    let min = min.unwrap_or_else(|| total(lo));
+    Ok(u64::try_from(min).map_err(|_| Overflow("total fuel"))?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use indoc::indoc;
-
-    #[test]
-    fn test_example() {
-        let input = indoc! {"
-            16,1,2,0,4,2,7,1,2,14
-        "};
-        assert_eq!(part1(input).unwrap(), 37);
-        assert_eq!(part2(input).unwrap(), 168);
+    use proptest::prelude::*;
+
+    // Tries every position, adding up the fuel one step at a time.
+    fn min_fuel_naive(positions: &[u32], increasing: bool) -> u64 {
+        let max = *positions.iter().max().unwrap();
+        (0..=max)
+            .map(|q| {
+                positions
+                    .iter()
+                    .map(|&p| {
+                        let steps = q.abs_diff(p);
+                        (1..=u64::from(steps))
+                            .map(|s| if increasing { s } else { 1 })
+                            .sum::<u64>()
+                    })
+                    .sum()
+            })
+            .min()
+            .unwrap()
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive(positions in prop::collection::vec(0..200u32, 1..30)) {
+            let input = positions.iter().map(u32::to_string).collect::<Vec<_>>().join(",");
+
+            prop_assert_eq!(part1(&input).unwrap(), min_fuel_naive(&positions, false));
+            prop_assert_eq!(part2(&input).unwrap(), min_fuel_naive(&positions, true));
+        }
     }
 }
@@ -1,87 +1,180 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use super::Result;
+use thiserror::Error;
 
-pub fn part1(input: &str) -> Result<usize> {
-    let outs = input
-        .lines()
-        .map(|s| {
-            let (_, out) = s.split_once(" | ").expect("separator `|`");
-            out.split(' ').collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
+use super::{
+    overflow::Overflow,
+    par,
+    parse::{self, ParseError},
+    Result, Solution,
+};
 
-    Ok(outs
-        .iter()
-        .map(|b| {
-            b.iter()
-                .filter(|s| matches!(s.len(), 2 | 4 | 3 | 7))
-                .count()
-        })
-        .sum())
+#[derive(Debug, Error)]
+enum ParseEntryError {
+    #[error("missing separator `|`")]
+    MissingSeparator,
+
+    #[error("found {0} signal patterns instead of 10")]
+    InvalidPatternCount(usize),
+
+    #[error("found {0} output values instead of 4")]
+    InvalidOutputCount(usize),
+
+    #[error("empty pattern")]
+    EmptyPattern,
+
+    #[error("invalid segment {0:?}, expected `a` to `g`")]
+    InvalidSegment(char),
 }
 
-pub fn part2(input: &str) -> Result<u32> {
-    let pairs = input
-        .lines()
-        .map(|s| {
-            let (sigs, out) = s.split_once(" | ").expect("separator `|`");
-            let sigs = sigs.split(' ').collect::<Vec<_>>();
-            let out = out.split(' ').collect::<Vec<_>>();
-            (sigs, out)
+#[derive(Debug, Error)]
+enum DecodeError {
+    #[error("no wiring of the segments matches the signal patterns `{0}`")]
+    NoMapping(String),
+
+    #[error("output value {0:?} is not a digit")]
+    UnknownDigit(String),
+}
+
+/// Parses a line of unique signal patterns and output values.
+fn parse_entry(s: &str) -> Result<(Vec<String>, Vec<String>), ParseError> {
+    if let Some((i, c)) = s
+        .char_indices()
+        .find(|&(_, c)| !matches!(c, 'a'..='g' | ' ' | '|'))
+    {
+        return Err(ParseError::new(
+            s,
+            &s[i..],
+            ParseEntryError::InvalidSegment(c),
+        ));
+    }
+    let (sigs_part, out_part) = s
+        .split_once(" | ")
+        .ok_or_else(|| ParseError::at_end(s, ParseEntryError::MissingSeparator))?;
+    let patterns = |part: &str| {
+        part.split(' ')
+            .map(|p| {
+                if p.is_empty() {
+                    Err(ParseError::new(s, p, ParseEntryError::EmptyPattern))
+                } else {
+                    Ok(p.to_owned())
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    let sigs = patterns(sigs_part)?;
+    if sigs.len() != 10 {
+        return Err(ParseError::new(
+            s,
+            sigs_part,
+            ParseEntryError::InvalidPatternCount(sigs.len()),
+        ));
+    }
+    let out = patterns(out_part)?;
+    if out.len() != 4 {
+        return Err(ParseError::new(
+            s,
+            out_part,
+            ParseEntryError::InvalidOutputCount(out.len()),
+        ));
+    }
+    Ok((sigs, out))
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    /// Pairs of unique signal patterns and output values.
+    type Input = Vec<(Vec<String>, Vec<String>)>;
+    type Part1 = usize;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::lines(input, parse_entry)?)
+    }
+
+    fn part1(pairs: &Self::Input) -> Result<usize> {
+        Ok(pairs
+            .iter()
+            .map(|(_, b)| {
+                b.iter()
+                    .filter(|s| matches!(s.len(), 2 | 4 | 3 | 7))
+                    .count()
+            })
+            .sum())
+    }
+
+    fn part2(pairs: &Self::Input) -> Result<u32> {
+        let values = par::map(pairs, |(a, b)| decode_output(a, b));
+        values.into_iter().try_fold(0u32, |sum, value| {
+            Ok(sum
+                .checked_add(value?)
+                .ok_or(Overflow("sum of output values"))?)
         })
-        .collect::<Vec<_>>();
+    }
+}
+
+pub fn part1(input: &str) -> Result<usize> {
+    Day08::solve_part1(input)
+}
 
-    Ok(pairs.iter().map(|(a, b)| decode_output(a, b)).sum())
+pub fn part2(input: &str) -> Result<u32> {
+    Day08::solve_part2(input)
 }
 
-fn decode_output(sigs: &[&str], outs: &[&str]) -> u32 {
-    let seg_mapping = find_mapping(sigs);
+fn decode_output(sigs: &[String], outs: &[String]) -> Result<u32, DecodeError> {
+    let seg_mapping = find_mapping(sigs).ok_or_else(|| DecodeError::NoMapping(sigs.join(" ")))?;
 
     let values = [
-        ("abcefg", '0'),
-        ("cf", '1'),
-        ("acdeg", '2'),
-        ("acdfg", '3'),
-        ("bcdf", '4'),
-        ("abdfg", '5'),
-        ("abdefg", '6'),
-        ("acf", '7'),
-        ("abcdefg", '8'),
-        ("abcdfg", '9'),
+        ("abcefg", 0),
+        ("cf", 1),
+        ("acdeg", 2),
+        ("acdfg", 3),
+        ("bcdf", 4),
+        ("abdfg", 5),
+        ("abdefg", 6),
+        ("acf", 7),
+        ("abcdefg", 8),
+        ("abcdfg", 9),
     ]
     .into_iter()
     .map(|(k, v)| (k.to_owned(), v))
-    .collect::<BTreeMap<String, _>>();
-
-    let out = outs
+    .collect::<BTreeMap<String, u32>>();
+    let decode = |pattern: &str| {
+        let mut chars = pattern
+            .chars()
+            .map(|c| seg_mapping.get(&c).copied())
+            .collect::<Option<Vec<_>>>()?;
+        chars.sort_unstable();
+        values.get(&chars.into_iter().collect::<String>()).copied()
+    };
+
+    // Make sure the mapping found is a wiring, every pattern must be a different digit.
+    let digits = sigs
         .iter()
-        .copied()
-        .map(|o| {
-            let mut chars = o
-                .chars()
-                .map(|c| seg_mapping.get(&c).expect("valid character [a-g]"))
-                .collect::<Vec<_>>();
-            chars.sort();
-            values
-                .get(&chars.into_iter().collect::<String>())
-                .expect("valid combination")
-        })
-        .collect::<String>();
+        .map(|s| decode(s))
+        .collect::<Option<BTreeSet<_>>>();
+    if digits.map_or(true, |d| d.len() != 10) {
+        return Err(DecodeError::NoMapping(sigs.join(" ")));
+    }
 
-    out.parse().unwrap()
+    outs.iter().try_fold(0, |value, o| {
+        let digit = decode(o).ok_or_else(|| DecodeError::UnknownDigit(o.clone()))?;
+        Ok(value * 10 + digit)
+    })
 }
 
-fn find_mapping(sigs: &[&str]) -> BTreeMap<char, char> {
+fn find_mapping(sigs: &[String]) -> Option<BTreeMap<char, char>> {
     // 1. Identify some digits with unique length:
     //    - '1' is 2 (c, f)
     //    - '7' is 3 (a, c, f)
     //    - '4' is 4 (b, c, d, f)
     //    - '8' is 7 (a, b, c, d, e, f, g)
-    let dig_1 = *sigs.iter().find(|s| s.len() == 2).expect("digit '1'");
-    let dig_7 = *sigs.iter().find(|s| s.len() == 3).expect("digit '7'");
-    let dig_4 = *sigs.iter().find(|s| s.len() == 4).expect("digit '4'");
-    let dig_8 = *sigs.iter().find(|s| s.len() == 7).expect("digit '8'");
+    let dig_1 = sigs.iter().find(|s| s.len() == 2)?;
+    let dig_7 = sigs.iter().find(|s| s.len() == 3)?;
+    let dig_4 = sigs.iter().find(|s| s.len() == 4)?;
+    let dig_8 = sigs.iter().find(|s| s.len() == 7)?;
 
     // 2. Identify segments 'b', 'e', 'f' by their unique frequencies:
     //    - 'b' is on 6 times
@@ -93,41 +186,27 @@ fn find_mapping(sigs: &[&str]) -> BTreeMap<char, char> {
             m
         })
     });
-    let seg_b = freqs
-        .iter()
-        .find_map(|(&k, &v)| (v == 6).then(|| k))
-        .expect("segment 'b'");
-    let seg_e = freqs
-        .iter()
-        .find_map(|(&k, &v)| (v == 4).then(|| k))
-        .expect("segment 'e'");
-    let seg_f = freqs
-        .iter()
-        .find_map(|(&k, &v)| (v == 9).then(|| k))
-        .expect("segment 'f'");
+    let seg_b = freqs.iter().find_map(|(&k, &v)| (v == 6).then_some(k))?;
+    let seg_e = freqs.iter().find_map(|(&k, &v)| (v == 4).then_some(k))?;
+    let seg_f = freqs.iter().find_map(|(&k, &v)| (v == 9).then_some(k))?;
 
     // 3. With 'f' defined, 'c' is the other segment in '1' (not f)
-    let seg_c = dig_1.chars().find(|&c| c != seg_f).expect("segment 'c'");
+    let seg_c = dig_1.chars().find(|&c| c != seg_f)?;
 
     // 4. With 'c' and 'f' defined, 'a' is the last unknown in '7' (not c, f)
-    let seg_a = dig_7
-        .chars()
-        .find(|&c| c != seg_c && c != seg_f)
-        .expect("segment 'a'");
+    let seg_a = dig_7.chars().find(|&c| c != seg_c && c != seg_f)?;
 
     // 5. 'd' is the last unknown in '4' (not b, c, f)
     let seg_d = dig_4
         .chars()
-        .find(|&c| c != seg_b && c != seg_c && c != seg_f)
-        .expect("segment 'd'");
+        .find(|&c| c != seg_b && c != seg_c && c != seg_f)?;
 
     // 6. 'g' is the last unknown in '8' (not a, b, c, d, e, f)
-    let seg_g = dig_8
-        .chars()
-        .find(|&c| c != seg_a && c != seg_b && c != seg_c && c != seg_d && c != seg_e && c != seg_f)
-        .expect("segment 'g'");
+    let seg_g = dig_8.chars().find(|&c| {
+        c != seg_a && c != seg_b && c != seg_c && c != seg_d && c != seg_e && c != seg_f
+    })?;
 
-    BTreeMap::from([
+    Some(BTreeMap::from([
         (seg_a, 'a'),
         (seg_b, 'b'),
         (seg_c, 'c'),
@@ -135,7 +214,7 @@ fn find_mapping(sigs: &[&str]) -> BTreeMap<char, char> {
         (seg_e, 'e'),
         (seg_f, 'f'),
         (seg_g, 'g'),
-    ])
+    ]))
 }
 
 #[cfg(test)]
@@ -143,20 +222,8 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_example() {
-        let input = indoc::indoc! {"
-            be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
-            edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
-            fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
-            fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
-            aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea
-            fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb
-            dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
-            bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
-            egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
-            gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
-        "};
-        assert_eq!(part1(input).unwrap(), 26);
-        assert_eq!(part2(input).unwrap(), 61229);
+    fn test_parse_error() {
+        let err = Day08::parse("ab cd\n").unwrap_err().to_string();
+        assert_eq!(err, "line 1, column 6 (\"ab cd\"): missing separator `|`");
     }
 }
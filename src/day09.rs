@@ -1,139 +1,134 @@
 use std::collections::{BTreeSet, VecDeque};
 
-use super::Result;
+use super::{
+    grid::{Grid, Pos},
+    image_export::{self, Export},
+    overflow::Overflow,
+    visualize::{palette, Frame, Renderer, Rgb, Visualize},
+    Result, Solution,
+};
 
-pub fn part1(input: &str) -> Result<u32> {
-    let heights: Vec<Vec<u32>> = input
-        .lines()
-        .map(|r| {
-            r.chars()
-                .map(|c| c.to_digit(10).expect("digit"))
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
-    let height = heights.len();
-    let width = heights[0].len();
-    let mut score = 0;
-    for i in 0..height {
-        for j in 0..width {
-            let v = heights[i][j];
-            if i > 0 && v >= heights[i - 1][j] {
-                continue;
-            }
-            if i + 1 < height && v >= heights[i + 1][j] {
-                continue;
-            }
-            if j > 0 && v >= heights[i][j - 1] {
-                continue;
-            }
-            if j + 1 < width && v >= heights[i][j + 1] {
-                continue;
-            }
-            score += v + 1;
-        }
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Grid<u8>;
+    type Part1 = u32;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(heights: &Self::Input) -> Result<u32> {
+        Ok(low_points(heights)
+            .try_fold(0u32, |sum, p| sum.checked_add(u32::from(heights[p]) + 1))
+            .ok_or(Overflow("sum of risk levels"))?)
     }
 
-    Ok(score)
+    fn part2(heights: &Self::Input) -> Result<usize> {
+        let mut basins = low_points(heights)
+            .map(|p| basin(p, heights).len())
+            .collect::<Vec<_>>();
+        basins.sort_by(|a, b| b.cmp(a));
+        Ok(basins
+            .iter()
+            .take(3)
+            .try_fold(1usize, |product, &size| product.checked_mul(size))
+            .ok_or(Overflow("product of basin sizes"))?)
+    }
+}
+
+pub fn part1(input: &str) -> Result<u32> {
+    Day09::solve_part1(input)
 }
 
 pub fn part2(input: &str) -> Result<usize> {
-    let heights: Vec<Vec<u32>> = input
-        .lines()
-        .map(|r| {
-            r.chars()
-                .map(|c| c.to_digit(10).expect("digit"))
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
-    let height = heights.len();
-    let width = heights[0].len();
-    let mut basins = Vec::new();
-    for i in 0..height {
-        for j in 0..width {
-            let v = heights[i][j];
-            if i > 0 && v >= heights[i - 1][j] {
-                continue;
-            }
-            if i + 1 < height && v >= heights[i + 1][j] {
-                continue;
-            }
-            if j > 0 && v >= heights[i][j - 1] {
-                continue;
-            }
-            if j + 1 < width && v >= heights[i][j + 1] {
-                continue;
+    Day09::solve_part2(input)
+}
+
+/// Positions lower than all of their adjacent positions.
+fn low_points(heights: &Grid<u8>) -> impl Iterator<Item = Pos> + '_ {
+    heights
+        .positions()
+        .filter(move |&p| heights.neighbors4(p).all(|q| heights[p] < heights[q]))
+}
+
+/// Positions flowing down to the low point `start`, nearest first.
+fn basin(start: Pos, map: &Grid<u8>) -> Vec<Pos> {
+    let mut visited = BTreeSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    let mut basin = Vec::new();
+    while let Some(p) = queue.pop_front() {
+        basin.push(p);
+        for q in map.neighbors4(p) {
+            if map[q] != 9 && map[p] < map[q] && visited.insert(q) {
+                queue.push_back(q);
             }
-            basins.push(basin_size(i, j, height, width, &heights));
         }
     }
-    basins.sort_by(|a, b| b.cmp(a));
-    Ok(basins.iter().take(3).product())
+
+    basin
 }
 
-fn basin_size(
-    start_i: usize,
-    start_j: usize,
-    height: usize,
-    width: usize,
-    map: &[Vec<u32>],
-) -> usize {
-    let mut visited = BTreeSet::from([(start_i, start_j)]);
-    let mut queue = VecDeque::from([(start_i, start_j)]);
-    let mut size = 0;
-    while !queue.is_empty() {
-        let (i, j) = queue.pop_front().unwrap();
-        size += 1;
-        if i + 1 < height
-            && map[i + 1][j] != 9
-            && map[i][j] < map[i + 1][j]
-            && !visited.contains(&(i + 1, j))
-        {
-            queue.push_back((i + 1, j));
-            visited.insert((i + 1, j));
-        }
-        if i > 0
-            && map[i - 1][j] != 9
-            && map[i][j] < map[i - 1][j]
-            && !visited.contains(&(i - 1, j))
-        {
-            queue.push_back((i - 1, j));
-            visited.insert((i - 1, j));
-        }
-        if j + 1 < width
-            && map[i][j + 1] != 9
-            && map[i][j] < map[i][j + 1]
-            && !visited.contains(&(i, j + 1))
-        {
-            queue.push_back((i, j + 1));
-            visited.insert((i, j + 1));
-        }
-        if j > 0
-            && map[i][j - 1] != 9
-            && map[i][j] < map[i][j - 1]
-            && !visited.contains(&(i, j - 1))
-        {
-            queue.push_back((i, j - 1));
-            visited.insert((i, j - 1));
-        }
+/// Fills the basins one at a time, from the top.
+struct Basins {
+    heights: Grid<u8>,
+    low_points: Vec<Pos>,
+    filled: Grid<Option<usize>>,
+    sizes: Vec<usize>,
+}
+
+impl Visualize for Day09 {
+    fn renderer(heights: Self::Input) -> Result<Box<dyn Renderer>> {
+        Ok(Box::new(Basins {
+            low_points: low_points(&heights).collect(),
+            filled: heights.map(|_| None),
+            heights,
+            sizes: Vec::new(),
+        }))
     }
+}
 
-    size
+/// Height map with every basin filled.
+impl Export for Day09 {
+    fn image(heights: Self::Input) -> Result<Grid<Rgb>> {
+        Ok(image_export::last_frame(Self::renderer(heights)?))
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_example() {
-        let input = indoc::indoc! {"
-            2199943210
-            3987894921
-            9856789892
-            8767896789
-            9899965678
-        "};
-        assert_eq!(part1(input).unwrap(), 15);
-        assert_eq!(part2(input).unwrap(), 1134);
+impl Renderer for Basins {
+    fn frame(&self) -> Frame {
+        let pixels = Grid::from_fn(self.heights.width(), self.heights.height(), |p| {
+            let shade = f64::from(self.heights[p]) / 9.0;
+            match self.filled[p] {
+                Some(i) => palette(i).mix(Rgb::BLACK, shade * 0.7),
+                None => Rgb(20, 20, 30).mix(Rgb(200, 200, 210), shade),
+            }
+        });
+        let mut largest = self.sizes.clone();
+        largest.sort_unstable_by(|a, b| b.cmp(a));
+        largest.truncate(3);
+        Frame {
+            pixels,
+            caption: format!(
+                "basin {}/{}, largest {:?}",
+                self.sizes.len(),
+                self.low_points.len(),
+                largest
+            ),
+        }
+    }
+
+    fn step(&mut self) -> bool {
+        let i = self.sizes.len();
+        let Some(&start) = self.low_points.get(i) else {
+            return false;
+        };
+        let basin = basin(start, &self.heights);
+        for &p in &basin {
+            self.filled[p] = Some(i);
+        }
+        self.sizes.push(basin.len());
+        true
     }
 }
@@ -1,11 +1,77 @@
-use super::Result;
+use std::io::BufRead;
 
-pub fn part1(input: &str) -> Result<usize> {
-    let mut points = 0;
-    for line in input.lines() {
-        points += check_line(line);
+use thiserror::Error;
+
+use super::{
+    overflow::Overflow,
+    par,
+    parse::{self, ParseError},
+    Result, Solution, Streaming,
+};
+
+#[derive(Debug, Error)]
+#[error("invalid character {0:?}, expected one of `()[]{{}}<>`")]
+struct InvalidCharError(char);
+
+#[derive(Debug, Error)]
+#[error("no incomplete lines to score")]
+struct NoIncompleteLines;
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<String>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::lines(input, |s| validate(s).map(str::to_owned))?)
+    }
+
+    fn part1(lines: &Self::Input) -> Result<usize> {
+        Ok(par::sum(lines, |line| check_line(line)))
+    }
+
+    fn part2(lines: &Self::Input) -> Result<usize> {
+        middle_score(par::map(lines, |line| check_line2(line)).into_iter())
+    }
+}
+
+// Part 2 keeps the score of each incomplete line to find the middle one,
+// which is much less than the lines themselves.
+impl Streaming for Day10 {
+    fn stream_part1(input: &mut dyn BufRead) -> Result<usize> {
+        let scores = parse::read_lines(input, |s| validate(s).map(check_line));
+        parse::until_error(scores, |scores| scores.sum())
+    }
+
+    fn stream_part2(input: &mut dyn BufRead) -> Result<usize> {
+        let scores = parse::read_lines(input, |s| validate(s).map(check_line2));
+        parse::until_error(scores, |scores| middle_score(scores))?
+    }
+}
+
+fn validate(s: &str) -> Result<&str, ParseError> {
+    match s.char_indices().find(|&(_, c)| !"()[]{}<>".contains(c)) {
+        Some((i, c)) => Err(ParseError::new(s, &s[i..], InvalidCharError(c))),
+        None => Ok(s),
     }
-    Ok(points)
+}
+
+fn middle_score(scores: impl Iterator<Item = Result<usize, Overflow>>) -> Result<usize> {
+    let mut scores = scores
+        .filter(|p| *p != Ok(0))
+        .collect::<Result<Vec<_>, _>>()?;
+    scores.sort_unstable();
+    Ok(*scores.get(scores.len() / 2).ok_or(NoIncompleteLines)?)
+}
+
+pub fn part1(input: &str) -> Result<usize> {
+    Day10::solve_part1(input)
+}
+
+pub fn part2(input: &str) -> Result<usize> {
+    Day10::solve_part2(input)
 }
 
 fn error_points(c: char) -> usize {
@@ -55,18 +121,6 @@ fn check_line(line: &str) -> usize {
     0
 }
 
-pub fn part2(input: &str) -> Result<usize> {
-    let mut scores = Vec::new();
-    for line in input.lines() {
-        let p = check_line2(line);
-        if p != 0 {
-            scores.push(p);
-        }
-    }
-    scores.sort_unstable();
-    Ok(scores[scores.len() / 2])
-}
-
 fn completion_points(c: char) -> usize {
     match c {
         ')' => 1,
@@ -77,7 +131,7 @@ fn completion_points(c: char) -> usize {
     }
 }
 
-fn check_line2(line: &str) -> usize {
+fn check_line2(line: &str) -> Result<usize, Overflow> {
     let mut state = Vec::new();
     for c in line.chars() {
         if matches!(c, '(' | '[' | '{' | '<') {
@@ -86,32 +140,32 @@ fn check_line2(line: &str) -> usize {
             match state.pop() {
                 Some('(') => {
                     if c != ')' {
-                        return 0;
+                        return Ok(0);
                     }
                 }
                 Some('[') => {
                     if c != ']' {
-                        return 0;
+                        return Ok(0);
                     }
                 }
                 Some('{') => {
                     if c != '}' {
-                        return 0;
+                        return Ok(0);
                     }
                 }
                 Some('<') => {
                     if c != '>' {
-                        return 0;
+                        return Ok(0);
                     }
                 }
                 Some(c) => unreachable!("unexpected {}", c),
                 None => {
-                    return 0;
+                    return Ok(0);
                 }
             }
         }
     }
-    let mut score = 0;
+    let mut score = 0usize;
     for c in state.iter().copied().rev() {
         let v = match c {
             '(' => ')',
@@ -120,31 +174,11 @@ fn check_line2(line: &str) -> usize {
             '<' => '>',
             c => unreachable!("unexpected {}", c),
         };
-        score = 5 * score + completion_points(v);
+        score = score
+            .checked_mul(5)
+            .and_then(|s| s.checked_add(completion_points(v)))
+            .ok_or(Overflow("completion score"))?;
     }
 
-    score
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_example() {
-        let input = indoc::indoc! {"
-            [({(<(())[]>[[{[]{<()<>>
-            [(()[<>])]({[<{<<[]>>(
-            {([(<{}[<>[]}>{[]{[(<()>
-            (((({<>}<{<{<>}{[]{[]{}
-            [[<[([]))<([[{}[[()]]]
-            [{[{({}]{}}([{[{{{}}([]
-            {<[[]]>}<{[{[{[]{()[[[]
-            [<(<(<(<{}))><([]([]()
-            <{([([[(<>()){}]>(<<{{
-            <{([{{}}[<[[[<>{}]]]>[]]
-        "};
-        assert_eq!(part1(input).unwrap(), 26397);
-        assert_eq!(part2(input).unwrap(), 288_957);
-    }
+    Ok(score)
 }
@@ -1,7 +1,22 @@
 use std::collections::VecDeque;
 
-use super::Result;
+use thiserror::Error;
 
+use super::{
+    grid::{Grid, Pos},
+    image_export::{self, Export},
+    visualize::{Frame, Renderer, Rgb, Visualize},
+    Result, Solution,
+};
+
+// Inputs synchronize within a few hundred steps, some grids never do.
+const MAX_STEPS: u32 = 10_000;
+
+#[derive(Debug, Error)]
+#[error("octopuses didn't all flash at once within {0} steps")]
+struct NeverSynchronized(u32);
+
+#[derive(Clone)]
 struct Octopus {
     energy: u32,
     flashed_at: u32,
@@ -16,143 +31,142 @@ impl Octopus {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Vec<Octopus>> {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| Octopus::new(c.to_digit(10).expect("digit")))
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>()
+pub struct Day11;
+
+impl Solution for Day11 {
+    /// Initial energy levels.
+    type Input = Grid<u8>;
+    type Part1 = usize;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(energies: &Self::Input) -> Result<usize> {
+        let mut octos = energies.map(|&e| Octopus::new(e.into()));
+        Ok((0..100).fold(0, |s, i| s + step(&mut octos, i + 1)))
+    }
+
+    fn part2(energies: &Self::Input) -> Result<u32> {
+        let mut octos = energies.map(|&e| Octopus::new(e.into()));
+        let all = octos.len();
+        (1..=MAX_STEPS)
+            .find(|&i| step(&mut octos, i) == all)
+            .ok_or_else(|| NeverSynchronized(MAX_STEPS).into())
+    }
+}
+
+/// Steps until all octopuses flash at once.
+struct Flashes {
+    octos: Grid<Octopus>,
+    steps: u32,
+    flashed: usize,
+    total: usize,
 }
 
-pub fn part1(input: &str) -> Result<usize> {
-    let mut octos = parse_input(input);
-    let h = octos.len();
-    let w = octos[0].len();
-    Ok((0..100).fold(0, |s, i| s + step(&mut octos, i + 1, h, w)))
+impl Visualize for Day11 {
+    fn renderer(energies: Self::Input) -> Result<Box<dyn Renderer>> {
+        Ok(Box::new(Flashes {
+            octos: energies.map(|&e| Octopus::new(e.into())),
+            steps: 0,
+            flashed: 0,
+            total: 0,
+        }))
+    }
 }
 
-pub fn part2(input: &str) -> Result<u32> {
-    let mut octos = parse_input(input);
-    let h = octos.len();
-    let w = octos[0].len();
-    let all = h * w;
-    let mut i = 0;
-    loop {
-        if step(&mut octos, i + 1, h, w) == all {
-            return Ok(i + 1);
+/// Number of times each octopus flashes in the first 100 steps.
+impl Export for Day11 {
+    fn image(energies: Self::Input) -> Result<Grid<Rgb>> {
+        let mut octos = energies.map(|&e| Octopus::new(e.into()));
+        let mut flashes = energies.map(|_| 0);
+        for i in 1..=100 {
+            step(&mut octos, i);
+            for p in octos.positions() {
+                if octos[p].flashed_at == i {
+                    flashes[p] += 1;
+                }
+            }
         }
-        i += 1;
+        let min = flashes.iter().copied().min().unwrap_or_default();
+        let max = flashes.iter().copied().max().unwrap_or_default();
+        Ok(flashes.map(|&n| image_export::heat(image_export::fraction(n, min, max))))
     }
 }
 
-fn step(octos: &mut Vec<Vec<Octopus>>, step: u32, height: usize, width: usize) -> usize {
-    for row in octos.iter_mut() {
-        for octo in row {
-            octo.energy += 1;
+impl Renderer for Flashes {
+    fn frame(&self) -> Frame {
+        Frame {
+            pixels: self.octos.map(|o| {
+                if o.flashed_at == self.steps && self.steps > 0 {
+                    Rgb(255, 250, 200)
+                } else {
+                    Rgb(10, 20, 60).mix(Rgb(60, 120, 220), f64::from(o.energy) / 9.0)
+                }
+            }),
+            caption: format!(
+                "step {}, {} flashed, {} flashes in total",
+                self.steps, self.flashed, self.total
+            ),
         }
     }
+
+    fn step(&mut self) -> bool {
+        if self.steps >= MAX_STEPS || (self.steps > 0 && self.flashed == self.octos.len()) {
+            return false;
+        }
+        self.steps += 1;
+        self.flashed = step(&mut self.octos, self.steps);
+        self.total += self.flashed;
+        true
+    }
+}
+
+pub fn part1(input: &str) -> Result<usize> {
+    Day11::solve_part1(input)
+}
+
+pub fn part2(input: &str) -> Result<u32> {
+    Day11::solve_part2(input)
+}
+
+fn step(octos: &mut Grid<Octopus>, step: u32) -> usize {
+    for octo in octos.iter_mut() {
+        octo.energy += 1;
+    }
     let mut count = 0;
-    for i in 0..height {
-        for j in 0..width {
-            if octos[i][j].energy > 9 {
-                count += flash(octos, i, j, height, width, step);
-            }
+    for p in octos.positions() {
+        if octos[p].energy > 9 {
+            count += flash(octos, p, step);
         }
     }
     count
 }
 
-fn flash(
-    octos: &mut Vec<Vec<Octopus>>,
-    start_i: usize,
-    start_j: usize,
-    height: usize,
-    width: usize,
-    step: u32,
-) -> usize {
-    let mut queue = VecDeque::from([(start_i, start_j)]);
+fn flash(octos: &mut Grid<Octopus>, start: Pos, step: u32) -> usize {
+    let mut queue = VecDeque::from([start]);
     let mut count = 0;
-    while !queue.is_empty() {
-        let (i, j) = queue.pop_front().expect("not empty");
-        if octos[i][j].flashed_at == step {
+    while let Some(p) = queue.pop_front() {
+        if octos[p].flashed_at == step {
             continue;
         }
         // Continue if this doesn't flash
-        if octos[i][j].energy <= 9 {
+        if octos[p].energy <= 9 {
             continue;
         }
 
         // Flashing affects neighbors
-        octos[i][j].energy = 0;
-        octos[i][j].flashed_at = step;
+        octos[p].energy = 0;
+        octos[p].flashed_at = step;
         count += 1;
-        // bottom
-        if i + 1 < height && octos[i + 1][j].flashed_at != step {
-            octos[i + 1][j].energy += 1;
-            queue.push_back((i + 1, j));
-        }
-        // bottom right
-        if i + 1 < height && j + 1 < width && octos[i + 1][j + 1].flashed_at != step {
-            octos[i + 1][j + 1].energy += 1;
-            queue.push_back((i + 1, j + 1));
-        }
-        // bottom left
-        if i + 1 < height && j > 0 && octos[i + 1][j - 1].flashed_at != step {
-            octos[i + 1][j - 1].energy += 1;
-            queue.push_back((i + 1, j - 1));
-        }
-        // top
-        if i > 0 && octos[i - 1][j].flashed_at != step {
-            octos[i - 1][j].energy += 1;
-            queue.push_back((i - 1, j));
-        }
-        // top right
-        if i > 0 && j + 1 < width && octos[i - 1][j + 1].flashed_at != step {
-            octos[i - 1][j + 1].energy += 1;
-            queue.push_back((i - 1, j + 1));
-        }
-        // top left
-        if i > 0 && j > 0 && octos[i - 1][j - 1].flashed_at != step {
-            octos[i - 1][j - 1].energy += 1;
-            queue.push_back((i - 1, j - 1));
-        }
-        // right
-        if j + 1 < width && octos[i][j + 1].flashed_at != step {
-            octos[i][j + 1].energy += 1;
-            queue.push_back((i, j + 1));
-        }
-        // left
-        if j > 0 && octos[i][j - 1].flashed_at != step {
-            octos[i][j - 1].energy += 1;
-            queue.push_back((i, j - 1));
+        for q in octos.neighbors8(p) {
+            if octos[q].flashed_at != step {
+                octos[q].energy += 1;
+                queue.push_back(q);
+            }
         }
     }
 
     count
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_example() {
-        let input = indoc::indoc! {"
-            5483143223
-            2745854711
-            5264556173
-            6141336146
-            6357385478
-            4167524645
-            2176841721
-            6882881134
-            4846848554
-            5283751526
-        "};
-        assert_eq!(part1(input).unwrap(), 1656);
-        assert_eq!(part2(input).unwrap(), 195);
-    }
-}
@@ -1,50 +1,114 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use super::Result;
-
-fn parse_input(input: &str) -> BTreeMap<&str, BTreeSet<&str>> {
-    input.lines().fold(BTreeMap::new(), |mut m, s| {
-        let (a, b) = s.split_once('-').expect("separator `-`");
-        m.entry(a).or_insert_with(BTreeSet::new).insert(b);
-        m.entry(b).or_insert_with(BTreeSet::new).insert(a);
-        m
-    })
+use thiserror::Error;
+
+use super::{
+    parse::{self, ParseError},
+    Result, Solution,
+};
+
+#[derive(Debug, Error)]
+enum ParseEdgeError {
+    #[error("missing separator `-`")]
+    MissingSeparator,
+
+    #[error("empty cave name")]
+    EmptyName,
+
+    #[error("cave name must be all lowercase or all uppercase letters")]
+    InvalidName,
+
+    #[error("big caves are connected, there would be infinitely many paths")]
+    BigCaves,
+}
+
+/// Parses a line like `start-A` into the connected caves.
+fn parse_edge(s: &str) -> Result<(&str, &str), ParseError> {
+    let (a, b) = s
+        .split_once('-')
+        .ok_or_else(|| ParseError::new(s, s, ParseEdgeError::MissingSeparator))?;
+    for name in [a, b] {
+        if name.is_empty() {
+            return Err(ParseError::new(s, name, ParseEdgeError::EmptyName));
+        }
+        if !(name.chars().all(|c| c.is_ascii_lowercase())
+            || name.chars().all(|c| c.is_ascii_uppercase()))
+        {
+            return Err(ParseError::new(s, name, ParseEdgeError::InvalidName));
+        }
+    }
+    if is_big(a) && is_big(b) {
+        return Err(ParseError::new(s, s, ParseEdgeError::BigCaves));
+    }
+    Ok((a, b))
+}
+
+fn is_big(name: &str) -> bool {
+    name.chars().all(|c| c.is_ascii_uppercase())
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    /// Adjacency list of caves.
+    type Input = BTreeMap<String, BTreeSet<String>>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let edges = parse::lines(input, parse_edge)?;
+        Ok(edges.into_iter().fold(BTreeMap::new(), |mut m, (a, b)| {
+            m.entry(a.to_owned())
+                .or_insert_with(BTreeSet::new)
+                .insert(b.to_owned());
+            m.entry(b.to_owned())
+                .or_insert_with(BTreeSet::new)
+                .insert(a.to_owned());
+            m
+        }))
+    }
+
+    fn part1(graph: &Self::Input) -> Result<usize> {
+        let mut current = Vec::new();
+        let mut paths = Vec::new();
+        let mut visited = BTreeMap::new();
+        find_paths1(
+            graph,
+            "start",
+            "end",
+            &mut current,
+            &mut paths,
+            &mut visited,
+        );
+        Ok(paths.len())
+    }
+
+    fn part2(graph: &Self::Input) -> Result<usize> {
+        let mut current = Vec::new();
+        let mut paths = Vec::new();
+        let mut visited = BTreeMap::new();
+        find_paths2(
+            graph,
+            "start",
+            "end",
+            &mut current,
+            &mut paths,
+            &mut visited,
+        );
+        Ok(paths.len())
+    }
 }
 
 pub fn part1(input: &str) -> Result<usize> {
-    let graph = parse_input(input);
-    let mut current = Vec::new();
-    let mut paths = Vec::new();
-    let mut visited = BTreeMap::new();
-    find_paths1(
-        &graph,
-        "start",
-        "end",
-        &mut current,
-        &mut paths,
-        &mut visited,
-    );
-    Ok(paths.len())
+    Day12::solve_part1(input)
 }
 
 pub fn part2(input: &str) -> Result<usize> {
-    let graph = parse_input(input);
-    let mut current = Vec::new();
-    let mut paths = Vec::new();
-    let mut visited = BTreeMap::new();
-    find_paths2(
-        &graph,
-        "start",
-        "end",
-        &mut current,
-        &mut paths,
-        &mut visited,
-    );
-    Ok(paths.len())
+    Day12::solve_part2(input)
 }
 
 fn find_paths1<'a>(
-    graph: &BTreeMap<&'a str, BTreeSet<&'a str>>,
+    graph: &'a BTreeMap<String, BTreeSet<String>>,
     u: &'a str,
     v: &'a str,
     current: &mut Vec<&'a str>,
@@ -75,7 +139,7 @@ fn find_paths1<'a>(
 }
 
 fn find_paths2<'a>(
-    graph: &BTreeMap<&'a str, BTreeSet<&'a str>>,
+    graph: &'a BTreeMap<String, BTreeSet<String>>,
     u: &'a str,
     v: &'a str,
     current: &mut Vec<&'a str>,
@@ -116,61 +180,20 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_example1() {
-        let input = indoc::indoc! {"
-            start-A
-            start-b
-            A-c
-            A-b
-            b-d
-            A-end
-            b-end
-        "};
-        assert_eq!(part1(input).unwrap(), 10);
-        assert_eq!(part2(input).unwrap(), 36);
-    }
+    fn test_parse_error() {
+        let err = Day12::parse("start-A\nA\n").unwrap_err().to_string();
+        assert_eq!(err, "line 2, column 1 (\"A\"): missing separator `-`");
 
-    #[test]
-    fn test_example2() {
-        let input = indoc::indoc! {"
-            dc-end
-            HN-start
-            start-kj
-            dc-start
-            dc-HN
-            LN-dc
-            HN-end
-            kj-sa
-            kj-HN
-            kj-dc
-        "};
-        assert_eq!(part1(input).unwrap(), 19);
-        assert_eq!(part2(input).unwrap(), 103);
-    }
+        let err = Day12::parse("start-A\nA-b1\n").unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "line 2, column 3 (\"A-b1\"): cave name must be all lowercase or all uppercase letters"
+        );
 
-    #[test]
-    fn test_example3() {
-        let input = indoc::indoc! {"
-            fs-end
-            he-DX
-            fs-he
-            start-DX
-            pj-DX
-            end-zg
-            zg-sl
-            zg-pj
-            pj-he
-            RW-he
-            fs-DX
-            pj-RW
-            zg-RW
-            start-pj
-            he-WI
-            zg-he
-            pj-fs
-            start-RW
-        "};
-        assert_eq!(part1(input).unwrap(), 226);
-        assert_eq!(part2(input).unwrap(), 3509);
+        let err = Day12::parse("start-A\nA-BC\n").unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "line 2, column 1 (\"A-BC\"): big caves are connected, there would be infinitely many paths"
+        );
     }
 }
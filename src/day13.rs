@@ -2,34 +2,140 @@ use std::collections::BTreeSet;
 
 use thiserror::Error;
 
-use super::Result;
+use super::{
+    grid::Grid,
+    image_export::{self, Export},
+    ocr::Letters,
+    parse::{self, ParseError},
+    visualize::{self, Frame, Renderer, Rgb, Visualize},
+    Result, Solution,
+};
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = (Vec<Dot>, Vec<Fold>);
+    type Part1 = usize;
+    type Part2 = Letters;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1((dots, folds): &Self::Input) -> Result<usize> {
+        let dots = folds
+            .iter()
+            .take(1)
+            .try_fold(dots.clone(), |dots, fold| fold_paper(&dots, fold))?;
+        Ok(dots.iter().copied().collect::<BTreeSet<_>>().len())
+    }
+
+    fn part2((dots, folds): &Self::Input) -> Result<Letters> {
+        let dots = folds
+            .iter()
+            .try_fold(dots.clone(), |dots, fold| fold_paper(&dots, fold))?;
+        Ok(Letters::from_picture(to_paper(&dots)?))
+    }
+}
+
+/// Folds the paper one fold at a time.
+struct Origami {
+    folds: Vec<Fold>,
+    /// Dots before the first fold and after each fold.
+    papers: Vec<Vec<Dot>>,
+    folded: usize,
+}
+
+impl Visualize for Day13 {
+    fn renderer((dots, folds): Self::Input) -> Result<Box<dyn Renderer>> {
+        let (width, height) = paper_size(&dots);
+        visualize::check_size(width, height)?;
+        // Folding first so that stepping can't fail, the paper only gets smaller.
+        let mut papers = vec![dots];
+        for fold in &folds {
+            let paper = fold_paper(papers.last().expect("the dots before folding"), fold)?;
+            papers.push(paper);
+        }
+        Ok(Box::new(Origami {
+            folds,
+            papers,
+            folded: 0,
+        }))
+    }
+}
+
+/// Paper after the last fold.
+impl Export for Day13 {
+    fn image(input: Self::Input) -> Result<Grid<Rgb>> {
+        Ok(image_export::last_frame(Self::renderer(input)?))
+    }
+}
+
+impl Renderer for Origami {
+    fn frame(&self) -> Frame {
+        let dots = &self.papers[self.folded];
+        let (width, height) = paper_size(dots);
+        let mut pixels = Grid::from_fn(width, height, |_| Rgb(20, 20, 40));
+        for &(x, y) in dots {
+            pixels[(y, x)] = Rgb(255, 215, 80);
+        }
+        let caption = match self.folded.checked_sub(1) {
+            Some(i) => format!(
+                "fold {}/{} along {}",
+                i + 1,
+                self.folds.len(),
+                self.folds[i]
+            ),
+            None => format!("{} folds to go", self.folds.len()),
+        };
+        Frame { pixels, caption }
+    }
+
+    fn step(&mut self) -> bool {
+        if self.folded == self.folds.len() {
+            return false;
+        }
+        self.folded += 1;
+        true
+    }
+}
+
+/// Width and height of the paper covering all dots.
+fn paper_size(dots: &[Dot]) -> (usize, usize) {
+    dots.iter().fold((0, 0), |(w, h), &(x, y)| {
+        (w.max(x.saturating_add(1)), h.max(y.saturating_add(1)))
+    })
+}
 
 pub fn part1(input: &str) -> Result<usize> {
-    let (dots, folds) = parse_input(input)?;
-    let dots = folds
-        .iter()
-        .take(1)
-        .fold(dots, |dots, fold| fold_paper(&dots, fold));
-    Ok(dots.iter().copied().collect::<BTreeSet<_>>().len())
+    Day13::solve_part1(input)
 }
 
-pub fn part2(input: &str) -> Result<String> {
-    let (dots, folds) = parse_input(input)?;
-    let dots = folds
-        .iter()
-        .fold(dots, |dots, fold| fold_paper(&dots, fold));
-    Ok(to_paper(&dots))
+pub fn part2(input: &str) -> Result<Letters> {
+    Day13::solve_part2(input)
 }
 
 #[derive(Debug, Error)]
 enum Error {
-    #[error("failed parse dots")]
-    ParseDots(#[source] ParseDotError),
+    #[error("missing fold instructions after an empty line")]
+    MissingFolds,
+}
 
-    #[error("failed parse fold instructions")]
-    ParseFolds(#[source] ParseFoldError),
+#[derive(Debug, Error)]
+enum PaperError {
+    #[error("dot at {0},{1} is past the edge of the paper folded along {2}")]
+    PastEdge(usize, usize, Fold),
+
+    #[error("no dots to draw")]
+    Empty,
+
+    #[error("paper of {0}x{1} is too large to draw")]
+    TooLarge(usize, usize),
 }
 
+// Much larger than any paper after folding, the letters are 40x6.
+const MAX_PAPER_AREA: usize = 1 << 24;
+
 #[derive(Debug, Error)]
 enum ParseFoldError {
     #[error("missing prefix `fold along`")]
@@ -57,133 +163,193 @@ enum ParseDotError {
 type Dot = (usize, usize);
 
 #[derive(Debug, Copy, Clone)]
-enum Fold {
+pub enum Fold {
     /// Fold along a horizontal line on y
     Up(usize),
     /// Fold along a vertical line on x
     Left(usize),
 }
 
+impl std::fmt::Display for Fold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fold::Up(y) => write!(f, "y={y}"),
+            Fold::Left(x) => write!(f, "x={x}"),
+        }
+    }
+}
+
 impl std::str::FromStr for Fold {
-    type Err = ParseFoldError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s
+        let line = s
             .strip_prefix("fold along ")
-            .ok_or(ParseFoldError::MissingPrefix)?;
+            .ok_or_else(|| ParseError::new(s, s, ParseFoldError::MissingPrefix))?;
 
-        if let Some(s) = s.strip_prefix("y=") {
-            Ok(Fold::Up(s.parse().map_err(ParseFoldError::ParseInt)?))
-        } else if let Some(s) = s.strip_prefix("x=") {
-            Ok(Fold::Left(s.parse().map_err(ParseFoldError::ParseInt)?))
+        let parse_int = |n: &str| {
+            n.parse()
+                .map_err(|e| ParseError::new(s, n, ParseFoldError::ParseInt(e)))
+        };
+        if let Some(n) = line.strip_prefix("y=") {
+            Ok(Fold::Up(parse_int(n)?))
+        } else if let Some(n) = line.strip_prefix("x=") {
+            Ok(Fold::Left(parse_int(n)?))
         } else {
-            Err(ParseFoldError::MissingDirection)
+            Err(ParseError::new(s, line, ParseFoldError::MissingDirection))
         }
     }
 }
 
-fn parse_input(input: &str) -> Result<(Vec<Dot>, Vec<Fold>), Error> {
-    let (dots, folds) = input.trim_end().split_once("\n\n").unwrap();
-    let dots = parse_dots(dots).map_err(Error::ParseDots)?;
-    let folds = folds
-        .lines()
-        .map(str::parse)
-        .collect::<Result<Vec<Fold>, ParseFoldError>>()
-        .map_err(Error::ParseFolds)?;
+fn parse_input(input: &str) -> Result<(Vec<Dot>, Vec<Fold>), ParseError> {
+    let (dots, folds) = input
+        .trim_end()
+        .split_once("\n\n")
+        .ok_or_else(|| ParseError::at_end(input, Error::MissingFolds))?;
+    let dots = parse::lines(dots, parse_dot).map_err(|e| e.within(input, dots))?;
+    let folds = parse::lines(folds, str::parse).map_err(|e| e.within(input, folds))?;
     Ok((dots, folds))
 }
 
-fn parse_dots(input: &str) -> Result<Vec<Dot>, ParseDotError> {
-    input
-        .lines()
-        .map(|s| {
-            let (x, y) = s.split_once(',').ok_or(ParseDotError::MissingComma)?;
-            Ok((
-                x.parse().map_err(ParseDotError::ParseX)?,
-                y.parse().map_err(ParseDotError::ParseY)?,
-            ))
-        })
-        .collect()
+fn parse_dot(s: &str) -> Result<Dot, ParseError> {
+    let (x, y) = s
+        .split_once(',')
+        .ok_or_else(|| ParseError::new(s, s, ParseDotError::MissingComma))?;
+    Ok((
+        x.parse()
+            .map_err(|e| ParseError::new(s, x, ParseDotError::ParseX(e)))?,
+        y.parse()
+            .map_err(|e| ParseError::new(s, y, ParseDotError::ParseY(e)))?,
+    ))
 }
 
-fn fold_paper(dots: &[Dot], fold: &Fold) -> Vec<Dot> {
-    match *fold {
-        Fold::Up(line_y) => dots
-            .iter()
-            .copied()
-            .map(|(x, y)| {
-                if y > line_y {
-                    (x, 2 * line_y - y)
-                } else {
-                    (x, y)
-                }
-            })
-            .collect(),
-        Fold::Left(line_x) => dots
-            .iter()
-            .copied()
-            .map(|(x, y)| {
-                if x > line_x {
-                    (2 * line_x - x, y)
-                } else {
-                    (x, y)
-                }
-            })
-            .collect(),
-    }
+// The part folded over can't be larger than the part kept, or it would fold off the paper.
+fn fold_paper(dots: &[Dot], fold: &Fold) -> Result<Vec<Dot>, PaperError> {
+    let reflect = |v: usize, line: usize| match v.checked_sub(line) {
+        Some(d) if d > line => None,
+        Some(d) => Some(line - d),
+        None => Some(v),
+    };
+    dots.iter()
+        .map(|&(x, y)| {
+            let folded = match *fold {
+                Fold::Up(line_y) => reflect(y, line_y).map(|y| (x, y)),
+                Fold::Left(line_x) => reflect(x, line_x).map(|x| (x, y)),
+            };
+            folded.ok_or(PaperError::PastEdge(x, y, *fold))
+        })
+        .collect()
 }
 
-fn to_paper(dots: &[Dot]) -> String {
+fn to_paper(dots: &[Dot]) -> Result<String, PaperError> {
     let dots = dots.iter().copied().collect::<BTreeSet<_>>();
-    let max_x = dots.iter().copied().map(|(x, _)| x).max().expect("max x");
-    let max_y = dots.iter().copied().map(|(_, y)| y).max().expect("max y");
-    (0..=max_y)
+    let max_x = dots
+        .iter()
+        .map(|&(x, _)| x)
+        .max()
+        .ok_or(PaperError::Empty)?;
+    let max_y = dots
+        .iter()
+        .map(|&(_, y)| y)
+        .max()
+        .ok_or(PaperError::Empty)?;
+    let (width, height) = (max_x.saturating_add(1), max_y.saturating_add(1));
+    if width.saturating_mul(height) > MAX_PAPER_AREA {
+        return Err(PaperError::TooLarge(width, height));
+    }
+    Ok((0..=max_y)
         .map(|j| {
             (0..=max_x)
                 .map(|i| if dots.contains(&(i, j)) { '#' } else { '.' })
                 .collect::<String>()
         })
         .collect::<Vec<_>>()
-        .join("\n")
+        .join("\n"))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
-    fn test_example() {
-        let input = indoc::indoc! {"
-            6,10
-            0,14
-            9,10
-            0,3
-            10,4
-            4,11
-            6,0
-            6,12
-            4,1
-            0,13
-            10,12
-            3,4
-            3,0
-            8,4
-            1,10
-            2,14
-            8,10
-            9,0
-
-            fold along y=7
-            fold along x=5
-        "};
-        let paper = indoc::indoc! {"
-            #####
-            #...#
-            #...#
-            #...#
-            #####
-        "};
-        assert_eq!(part1(input).unwrap(), 17);
-        assert_eq!(part2(input).unwrap(), paper.trim_end());
+    fn test_parse_error() {
+        let err = parse_input("6,10\n0,14\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+
+        let err = parse_input("6,10\n\nfold along y=7\nfold along z=5\n").unwrap_err();
+        assert_eq!((err.line, err.column), (4, 12));
+    }
+
+    type Folds = Vec<(char, usize)>;
+
+    // Folds where the part folded over is at most as large as the part kept, with dots
+    // anywhere on the paper. Dots on a fold line are removed, the puzzle doesn't have them.
+    fn origami() -> impl Strategy<Value = (Vec<Dot>, Folds)> {
+        let axes = prop::collection::vec((prop::sample::select(vec!['x', 'y']), 0..100usize), 1..5);
+        let dots = prop::collection::vec((0..1000usize, 0..1000usize), 1..40);
+        (1..6usize, 1..6usize, axes, dots).prop_map(|(mut w, mut h, axes, dots)| {
+            let mut folds = axes
+                .into_iter()
+                .rev()
+                .map(|(axis, r)| {
+                    let len = if axis == 'x' { &mut w } else { &mut h };
+                    let at = *len;
+                    *len = at + 1 + r % (at + 1);
+                    (axis, at)
+                })
+                .collect::<Vec<_>>();
+            folds.reverse();
+            let dots = dots
+                .into_iter()
+                .map(|(x, y)| (x % w, y % h))
+                .filter(|&dot| fold_naive(dot, &folds).is_some())
+                .collect();
+            (dots, folds)
+        })
+    }
+
+    // Folds a single dot, `None` if it's on a fold line.
+    fn fold_naive((mut x, mut y): Dot, folds: &[(char, usize)]) -> Option<Dot> {
+        for &(axis, at) in folds {
+            let v = if axis == 'x' { &mut x } else { &mut y };
+            if *v == at {
+                return None;
+            }
+            if *v > at {
+                *v = 2 * at - *v;
+            }
+        }
+        Some((x, y))
+    }
+
+    proptest! {
+        #[test]
+        fn test_matches_naive((dots, folds) in origami()) {
+            prop_assume!(!dots.is_empty());
+            let dots_input = dots.iter().map(|(x, y)| format!("{x},{y}")).collect::<Vec<_>>();
+            let folds_input = folds
+                .iter()
+                .map(|(axis, at)| format!("fold along {axis}={at}"))
+                .collect::<Vec<_>>();
+            let input = format!("{}\n\n{}\n", dots_input.join("\n"), folds_input.join("\n"));
+
+            let first = dots
+                .iter()
+                .filter_map(|&d| fold_naive(d, &folds[..1]))
+                .collect::<BTreeSet<_>>();
+            let last = dots
+                .iter()
+                .filter_map(|&d| fold_naive(d, &folds))
+                .collect::<BTreeSet<_>>();
+            let (w, h) = last.iter().fold((0, 0), |(w, h), &(x, y)| (w.max(x + 1), h.max(y + 1)));
+            let picture = (0..h)
+                .map(|y| (0..w).map(|x| if last.contains(&(x, y)) { '#' } else { '.' }).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            prop_assert_eq!(part1(&input).unwrap(), first.len());
+            prop_assert_eq!(part2(&input).unwrap().picture, picture);
+        }
     }
 }
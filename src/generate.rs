@@ -0,0 +1,537 @@
+//! Random puzzle inputs for stress testing.
+//!
+//! Each generator writes an input valid for the day's parser, with `size` controlling
+//! how large it is, e.g., the number of lines. The same seed gives the same input.
+
+use std::{
+    collections::BTreeSet,
+    io::{self, Write},
+};
+
+use crate::{day11::Day11, grid::Grid, ocr, Solution};
+
+/// Small and fast pseudorandom number generator (`SplitMix64`), good enough for inputs.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Random number in `0..n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "empty range");
+        // The bias is negligible for the small ranges used here.
+        #[allow(clippy::cast_possible_truncation)]
+        let r = (self.next_u64() % n as u64) as usize;
+        r
+    }
+
+    /// Random number in `lo..=hi`.
+    pub fn between(&mut self, lo: usize, hi: usize) -> usize {
+        lo + self.below(hi - lo + 1)
+    }
+
+    /// Returns `true` with probability `percent`%.
+    pub fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    /// `k` distinct numbers from `0..n` in random order.
+    pub fn sample(&mut self, n: usize, k: usize) -> Vec<usize> {
+        let mut all = (0..n).collect::<Vec<_>>();
+        self.shuffle(&mut all);
+        all.truncate(k);
+        all
+    }
+}
+
+type GenerateFn = fn(&mut Rng, usize, &mut dyn Write) -> io::Result<()>;
+
+/// Generator of inputs for a day.
+#[derive(Clone, Copy)]
+pub struct Generator {
+    pub day: u8,
+    /// What `size` is, e.g., `depths`.
+    pub size: &'static str,
+    /// Size close to the puzzle input.
+    pub default_size: usize,
+    generate: GenerateFn,
+}
+
+impl Generator {
+    /// Writes the input generated from `seed` to `out`.
+    pub fn write(&self, seed: u64, size: usize, out: &mut dyn Write) -> io::Result<()> {
+        (self.generate)(&mut Rng::new(seed), size, out)
+    }
+
+    /// Generates the input from `seed`.
+    #[must_use]
+    pub fn generate(&self, seed: u64, size: usize) -> String {
+        let mut out = Vec::new();
+        self.write(seed, size, &mut out).expect(
+            "writing to a Vec can't fail, and day 11 synchronizes well within its attempts",
+        );
+        String::from_utf8(out).expect("inputs are ASCII")
+    }
+}
+
+impl std::fmt::Debug for Generator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Generator")
+            .field("day", &self.day)
+            .field("size", &self.size)
+            .field("default_size", &self.default_size)
+            .finish_non_exhaustive()
+    }
+}
+
+macro_rules! generators {
+    ($($day:literal => $generate:ident($size:literal, $default_size:literal)),* $(,)?) => {
+        &[$(Generator {
+            day: $day,
+            size: $size,
+            default_size: $default_size,
+            generate: $generate,
+        }),*]
+    };
+}
+
+/// All generators, ordered by day.
+pub static GENERATORS: &[Generator] = generators![
+    1 => depths("depths", 2000),
+    2 => commands("commands", 1000),
+    3 => report("numbers", 1000),
+    4 => bingo("boards", 100),
+    5 => segments("segments", 500),
+    6 => lanternfish("fish", 300),
+    7 => crabs("crabs", 1000),
+    8 => displays("entries", 200),
+    9 => heightmap("rows and columns", 100),
+    10 => navigation("lines", 100),
+    11 => octopuses("rows and columns", 10),
+    12 => caves("caves", 12),
+    13 => origami("dots", 800),
+];
+
+/// Finds the generator for `day`.
+#[must_use]
+pub fn get_generator(day: u8) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|g| g.day == day)
+}
+
+fn depths(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let mut depth = rng.between(100, 200);
+    for _ in 0..size {
+        writeln!(out, "{depth}")?;
+        depth = (depth + rng.below(40)).saturating_sub(15);
+    }
+    Ok(())
+}
+
+// `up` never makes the aim negative, so the position stays below the surface.
+fn commands(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let mut aim = 0;
+    for _ in 0..size {
+        let n = rng.between(1, 9);
+        match rng.below(10) {
+            0..=3 => writeln!(out, "forward {n}")?,
+            4..=6 if aim >= n => {
+                aim -= n;
+                writeln!(out, "up {n}")?;
+            }
+            _ => {
+                aim += n;
+                writeln!(out, "down {n}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Distinct numbers, wide enough to have `size` of them.
+fn report(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let size = size.max(1);
+    let mut width = 12;
+    while 1 << width < size {
+        width += 1;
+    }
+    for n in rng.sample(1 << width, size) {
+        writeln!(out, "{n:0width$b}")?;
+    }
+    Ok(())
+}
+
+// All numbers are drawn, so every board wins.
+fn bingo(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let draws = rng.sample(100, 100);
+    let draws = draws.iter().map(usize::to_string).collect::<Vec<_>>();
+    writeln!(out, "{}", draws.join(","))?;
+    for _ in 0..size.max(1) {
+        writeln!(out)?;
+        for row in rng.sample(100, 25).chunks(5) {
+            let row = row.iter().map(|n| format!("{n:>2}")).collect::<Vec<_>>();
+            writeln!(out, "{}", row.join(" "))?;
+        }
+    }
+    Ok(())
+}
+
+// Horizontal, vertical or diagonal segments in a 1000x1000 area.
+fn segments(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    const SIZE: usize = 1000;
+    for _ in 0..size {
+        let (x1, y1) = (rng.below(SIZE), rng.below(SIZE));
+        let (x2, y2) = match rng.below(3) {
+            0 => (rng.below(SIZE), y1),
+            1 => (x1, rng.below(SIZE)),
+            _ => {
+                let (left, up) = (rng.chance(50), rng.chance(50));
+                let room_x = if left { x1 } else { SIZE - 1 - x1 };
+                let room_y = if up { y1 } else { SIZE - 1 - y1 };
+                let d = rng.below(room_x.min(room_y) + 1);
+                let x2 = if left { x1 - d } else { x1 + d };
+                let y2 = if up { y1 - d } else { y1 + d };
+                (x2, y2)
+            }
+        };
+        writeln!(out, "{x1},{y1} -> {x2},{y2}")?;
+    }
+    Ok(())
+}
+
+fn comma_separated(
+    out: &mut dyn Write,
+    size: usize,
+    mut f: impl FnMut() -> usize,
+) -> io::Result<()> {
+    for i in 0..size.max(1) {
+        if i > 0 {
+            write!(out, ",")?;
+        }
+        write!(out, "{}", f())?;
+    }
+    writeln!(out)
+}
+
+fn lanternfish(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    comma_separated(out, size, || rng.between(1, 5))
+}
+
+fn crabs(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    comma_separated(out, size, || rng.below(2000))
+}
+
+const DIGITS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+// Each entry wires the segments differently and lists the patterns in a random order.
+fn displays(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    for _ in 0..size {
+        let mut wires = *b"abcdefg";
+        rng.shuffle(&mut wires);
+        let pattern = |rng: &mut Rng, digit: usize| {
+            let mut p = DIGITS[digit]
+                .bytes()
+                .map(|b| wires[usize::from(b - b'a')])
+                .collect::<Vec<_>>();
+            rng.shuffle(&mut p);
+            String::from_utf8(p).expect("segments are ASCII")
+        };
+        let patterns = rng
+            .sample(10, 10)
+            .into_iter()
+            .map(|d| pattern(rng, d))
+            .collect::<Vec<_>>();
+        let output = (0..4)
+            .map(|_| {
+                let d = rng.below(10);
+                pattern(rng, d)
+            })
+            .collect::<Vec<_>>();
+        writeln!(out, "{} | {}", patterns.join(" "), output.join(" "))?;
+    }
+    Ok(())
+}
+
+fn write_digits(out: &mut dyn Write, grid: &Grid<u8>) -> io::Result<()> {
+    writeln!(out, "{grid}")
+}
+
+// Ridges of 9s separate the basins.
+fn heightmap(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let size = size.max(1);
+    #[allow(clippy::cast_possible_truncation)]
+    let grid = Grid::from_fn(size, size, |_| {
+        if rng.chance(20) {
+            9
+        } else {
+            rng.below(9) as u8
+        }
+    });
+    write_digits(out, &grid)
+}
+
+const OPEN: &[u8] = b"([{<";
+const CLOSE: &[u8] = b")]}>";
+
+// Completion scores grow 5 times per missing bracket, so they're kept within `usize`.
+const MAX_DEPTH: usize = 12;
+
+// An odd number of lines are incomplete so there's a middle score, the rest are corrupted.
+fn navigation(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let size = size.max(1);
+    let incomplete = (size / 2) | 1;
+    let mut kinds = (0..size).map(|i| i < incomplete).collect::<Vec<_>>();
+    rng.shuffle(&mut kinds);
+    for incomplete in kinds {
+        let len = rng.between(80, 110);
+        let corrupt_at = rng.below(len);
+        let mut stack = Vec::new();
+        let mut line = Vec::with_capacity(len);
+        for i in 0..len {
+            if !incomplete && i == corrupt_at {
+                let expected = stack.pop().map(|o| CLOSE[o]);
+                let wrong = CLOSE
+                    .iter()
+                    .copied()
+                    .filter(|&c| Some(c) != expected)
+                    .collect::<Vec<_>>();
+                line.push(*rng.choose(&wrong));
+                stack.clear();
+            } else if stack.is_empty() || (stack.len() < MAX_DEPTH && rng.chance(55)) {
+                let o = rng.below(4);
+                stack.push(o);
+                line.push(OPEN[o]);
+            } else if let Some(o) = stack.pop() {
+                line.push(CLOSE[o]);
+            }
+        }
+        if incomplete && stack.is_empty() {
+            line.push(*rng.choose(OPEN));
+        }
+        writeln!(
+            out,
+            "{}",
+            String::from_utf8(line).expect("brackets are ASCII")
+        )?;
+    }
+    Ok(())
+}
+
+// A patch of random energies as large as the puzzle's, in a background at one level with
+// some random octopuses. The background flashing together pulls the rest along, but a grid
+// can still settle into flashing out of step, so it's drawn again until it synchronizes.
+fn octopuses(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    const ATTEMPTS: usize = 100;
+    let size = size.max(1);
+    let patch = size.min(10);
+    for _ in 0..ATTEMPTS {
+        let level = rng.below(10);
+        let (top, left) = (rng.below(size - patch + 1), rng.below(size - patch + 1));
+        #[allow(clippy::cast_possible_truncation)]
+        let grid = Grid::from_fn(size, size, |(i, j)| {
+            let in_patch = (top..top + patch).contains(&i) && (left..left + patch).contains(&j);
+            if in_patch || rng.chance(10) {
+                rng.below(10) as u8
+            } else {
+                level as u8
+            }
+        });
+        if Day11::part2(&grid).is_ok() {
+            return write_digits(out, &grid);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        format!("no synchronizing grid in {ATTEMPTS} attempts"),
+    ))
+}
+
+// A random tree connects all caves, then extra tunnels add more paths.
+// Big caves are never connected to each other, or there would be infinitely many paths.
+fn caves(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let size = size.min(26 * 26);
+    let name = |n: usize, big: bool| {
+        let a = if big { b'A' } else { b'a' };
+        #[allow(clippy::cast_possible_truncation)]
+        let name = [a + (n / 26) as u8, a + (n % 26) as u8];
+        String::from_utf8(name.to_vec()).expect("names are ASCII")
+    };
+    let big_count = size / 4;
+    let mut caves = vec![("start".to_owned(), false), ("end".to_owned(), false)];
+    caves.extend(
+        rng.sample(26 * 26, big_count)
+            .into_iter()
+            .map(|n| (name(n, true), true)),
+    );
+    caves.extend(
+        rng.sample(26 * 26, size - big_count)
+            .into_iter()
+            .map(|n| (name(n, false), false)),
+    );
+    rng.shuffle(&mut caves[1..]);
+
+    let mut tunnels = BTreeSet::new();
+    for i in 1..caves.len() {
+        let small = (0..i).filter(|&j| !caves[j].1).collect::<Vec<_>>();
+        let j = if caves[i].1 {
+            *rng.choose(&small)
+        } else {
+            rng.below(i)
+        };
+        tunnels.insert((j, i));
+    }
+    for _ in 0..size / 2 {
+        let (i, j) = (rng.below(caves.len()), rng.below(caves.len()));
+        if i != j && !(caves[i].1 && caves[j].1) {
+            tunnels.insert((i.min(j), i.max(j)));
+        }
+    }
+
+    let mut tunnels = tunnels.into_iter().collect::<Vec<_>>();
+    rng.shuffle(&mut tunnels);
+    for (i, j) in tunnels {
+        let (a, b) = if rng.chance(50) { (i, j) } else { (j, i) };
+        writeln!(out, "{}-{}", caves[a].0, caves[b].0)?;
+    }
+    Ok(())
+}
+
+// Folds like the puzzle input, down to 40x6.
+const FOLDS: [(char, usize); 12] = [
+    ('x', 655),
+    ('y', 447),
+    ('x', 327),
+    ('y', 223),
+    ('x', 163),
+    ('y', 111),
+    ('x', 81),
+    ('y', 55),
+    ('x', 40),
+    ('y', 27),
+    ('y', 13),
+    ('y', 6),
+];
+
+// Random letters are drawn, then each dot is unfolded to either side of each fold.
+fn origami(rng: &mut Rng, size: usize, out: &mut dyn Write) -> io::Result<()> {
+    let alphabet = ocr::alphabet().collect::<Vec<_>>();
+    let text = (0..8).map(|_| *rng.choose(&alphabet)).collect::<String>();
+    let picture = ocr::draw(&text).expect("letters are from the font");
+    let lit = picture
+        .lines()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.char_indices()
+                .filter(|&(_, c)| c == '#')
+                .map(move |(x, _)| (x, y))
+        })
+        .collect::<Vec<_>>();
+
+    let mut unfold = |(mut x, mut y): (usize, usize)| {
+        for &(axis, at) in FOLDS.iter().rev() {
+            if rng.chance(50) {
+                match axis {
+                    'x' => x = 2 * at - x,
+                    _ => y = 2 * at - y,
+                }
+            }
+        }
+        (x, y)
+    };
+    let mut dots = lit.iter().map(|&p| unfold(p)).collect::<BTreeSet<_>>();
+    for i in 0..size.saturating_sub(lit.len()) {
+        dots.insert(unfold(lit[i % lit.len()]));
+    }
+
+    let mut dots = dots.into_iter().collect::<Vec<_>>();
+    rng.shuffle(&mut dots);
+    for (x, y) in dots {
+        writeln!(out, "{x},{y}")?;
+    }
+    writeln!(out)?;
+    for (axis, at) in FOLDS {
+        writeln!(out, "fold along {axis}={at}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_inputs() {
+        assert!(GENERATORS.windows(2).all(|w| w[0].day < w[1].day));
+        for generator in GENERATORS {
+            let day = crate::get_day(generator.day).unwrap();
+            for seed in 0..5 {
+                let size = generator.default_size / 4 + 1;
+                let input = generator.generate(seed, size);
+                let parsed = day
+                    .parse(&input)
+                    .unwrap_or_else(|e| panic!("day {} seed {}: {}", generator.day, seed, e));
+                parsed.part1().unwrap();
+                parsed.part2().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_seed() {
+        let generator = get_generator(5).unwrap();
+        assert_eq!(generator.generate(1, 10), generator.generate(1, 10));
+        assert_ne!(generator.generate(1, 10), generator.generate(2, 10));
+        assert_eq!(generator.generate(1, 10).lines().count(), 10);
+    }
+
+    #[test]
+    fn test_large_octopuses() {
+        let generator = get_generator(11).unwrap();
+        for seed in 0..3 {
+            let input = generator.generate(seed, 60);
+            assert_eq!(input.lines().count(), 60);
+            let steps = crate::day11::part2(&input).unwrap();
+            // A grid of one level synchronizes within 10 steps, this one takes longer.
+            assert!(steps > 10, "seed {seed}: {steps} steps");
+        }
+    }
+
+    #[test]
+    fn test_origami_letters() {
+        let input = get_generator(13).unwrap().generate(7, 100);
+        let letters = crate::day13::part2(&input).unwrap();
+        assert_eq!(letters.text.len(), 8);
+        assert!(
+            letters.text.chars().all(|c| c != '?'),
+            "{}",
+            letters.picture
+        );
+    }
+}
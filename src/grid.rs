@@ -0,0 +1,305 @@
+//! Two-dimensional grid stored in a flat `Vec`, indexed by `(row, column)`.
+
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use thiserror::Error;
+
+use super::parse::{self, ParseError};
+
+/// Position in a grid as `(row, column)`.
+pub type Pos = (usize, usize);
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum ParseGridError {
+    #[error("empty grid")]
+    Empty,
+
+    #[error("found {len} columns instead of {expected}")]
+    UnevenRow { len: usize, expected: usize },
+
+    #[error("invalid digit {0:?}")]
+    InvalidDigit(char),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+// Offsets to neighbors plus 1, so `(1, 1)` is the position itself.
+const OFFSETS4: [(usize, usize); 4] = [(0, 1), (1, 0), (1, 2), (2, 1)];
+const OFFSETS8: [(usize, usize); 8] = [
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (1, 0),
+    (1, 2),
+    (2, 0),
+    (2, 1),
+    (2, 2),
+];
+
+impl<T> Grid<T> {
+    /// Creates a grid from cells in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cells.len()` is not `width * height`.
+    #[must_use]
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "cells must fill the grid");
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Creates a grid with the value of each cell computed from its position.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|i| (0..width).map(move |j| (i, j)))
+            .map(&mut f)
+            .collect();
+        Self::new(width, height, cells)
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    #[must_use]
+    pub fn get(&self, (i, j): Pos) -> Option<&T> {
+        (i < self.height && j < self.width).then(|| &self.cells[i * self.width + j])
+    }
+
+    pub fn get_mut(&mut self, (i, j): Pos) -> Option<&mut T> {
+        if i < self.height && j < self.width {
+            Some(&mut self.cells[i * self.width + j])
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over cells in row-major order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    /// Iterates over all positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |i| (0..width).map(move |j| (i, j)))
+    }
+
+    /// Row `i` as a slice.
+    #[must_use]
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.cells[i * self.width..(i + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> std::slice::Chunks<'_, T> {
+        // `max(1)` because `chunks` panics on 0, the iterator is empty anyway.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Column `j` from top to bottom.
+    pub fn column(&self, j: usize) -> impl Iterator<Item = &T> {
+        assert!(j < self.width, "column out of bounds");
+        self.cells.iter().skip(j).step_by(self.width)
+    }
+
+    /// Horizontally and vertically adjacent positions within the grid.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        self.neighbors(pos, &OFFSETS4)
+    }
+
+    /// Horizontally, vertically and diagonally adjacent positions within the grid.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        self.neighbors(pos, &OFFSETS8)
+    }
+
+    fn neighbors(
+        &self,
+        (i, j): Pos,
+        offsets: &'static [(usize, usize)],
+    ) -> impl Iterator<Item = Pos> {
+        let (height, width) = (self.height, self.width);
+        offsets.iter().filter_map(move |&(di, dj)| {
+            let i = (i + di).checked_sub(1)?;
+            let j = (j + dj).checked_sub(1)?;
+            (i < height && j < width).then_some((i, j))
+        })
+    }
+
+    /// Creates a grid of the same size by applying `f` to each cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Grid<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Grid<T> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): Pos) -> &T {
+        assert!(i < self.height && j < self.width, "position out of bounds");
+        &self.cells[i * self.width + j]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, (i, j): Pos) -> &mut T {
+        assert!(i < self.height && j < self.width, "position out of bounds");
+        &mut self.cells[i * self.width + j]
+    }
+}
+
+/// Parses a grid of single digits, one row per line.
+impl std::str::FromStr for Grid<u8> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let rows = parse::lines(s, |line| {
+            let row = line
+                .char_indices()
+                .map(|(i, c)| match c.to_digit(10) {
+                    #[allow(clippy::cast_possible_truncation)]
+                    Some(d) => Ok(d as u8),
+                    None => Err(ParseError::new(
+                        line,
+                        &line[i..],
+                        ParseGridError::InvalidDigit(c),
+                    )),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let expected = *width.get_or_insert(row.len());
+            if row.len() == expected {
+                Ok(row)
+            } else {
+                Err(ParseError::new(
+                    line,
+                    line,
+                    ParseGridError::UnevenRow {
+                        len: row.len(),
+                        expected,
+                    },
+                ))
+            }
+        })?;
+
+        match width {
+            Some(width) if width > 0 => Ok(Self::new(width, rows.len(), rows.concat())),
+            _ => Err(ParseError::new(s, s, ParseGridError::Empty)),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid: Grid<u8> = "123\n456\n".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 6);
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), [2, 5]);
+        assert_eq!(grid.to_string(), "123\n456");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |s: &str| {
+            let e = s.parse::<Grid<u8>>().unwrap_err();
+            let kind = e.kind().downcast_ref::<ParseGridError>().cloned();
+            (e.line, e.column, kind)
+        };
+        assert_eq!(err(""), (1, 1, Some(ParseGridError::Empty)));
+        assert_eq!(
+            err("12\n3"),
+            (
+                2,
+                1,
+                Some(ParseGridError::UnevenRow {
+                    len: 1,
+                    expected: 2
+                })
+            )
+        );
+        assert_eq!(
+            err("12\n3x"),
+            (2, 2, Some(ParseGridError::InvalidDigit('x')))
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::from_fn(3, 3, |p| p);
+        assert_eq!(
+            grid.neighbors4((0, 0)).collect::<Vec<_>>(),
+            [(0, 1), (1, 0)]
+        );
+        assert_eq!(grid.neighbors8((0, 2)).count(), 3);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbors4((2, 1)).count(), 3);
+    }
+}
@@ -0,0 +1,318 @@
+//! Static images of some days, written as PPM, SVG or, with the `png` feature, PNG.
+//!
+//! An [`Export`] day draws its result as a grid of pixels, [`write`] encodes it scaled up
+//! so that each cell is a square of pixels.
+
+use std::{
+    fmt,
+    io::{self, Write},
+    path::Path,
+};
+
+use thiserror::Error;
+
+use crate::{
+    day05::Day05,
+    day09::Day09,
+    day11::Day11,
+    day13::Day13,
+    grid::Grid,
+    visualize::{self, Renderer, Rgb, TooLarge},
+    Result, Solution,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Binary PPM (P6), readable by most image tools.
+    Ppm,
+    /// One rectangle per run of cells of the same color.
+    Svg,
+    Png,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("unknown image format `{0}`: expected ppm, svg or png")]
+pub struct UnknownFormat(pub String);
+
+impl std::str::FromStr for Format {
+    type Err = UnknownFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ppm" => Ok(Format::Ppm),
+            "svg" => Ok(Format::Svg),
+            "png" => Ok(Format::Png),
+            _ => Err(UnknownFormat(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::Ppm => "ppm",
+            Format::Svg => "svg",
+            Format::Png => "png",
+        })
+    }
+}
+
+impl Format {
+    /// Format named by the extension of `path`.
+    pub fn from_path(path: &Path) -> Result<Self, UnknownFormat> {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .parse()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    TooLarge(#[from] TooLarge),
+
+    #[error("PNG needs the `png` feature, build with `--features png`")]
+    PngDisabled,
+
+    #[cfg(feature = "png")]
+    #[error(transparent)]
+    Png(#[from] png::EncodingError),
+}
+
+/// Writes `pixels` in `format`, each cell as a `scale` by `scale` square.
+pub fn write(
+    pixels: &Grid<Rgb>,
+    format: Format,
+    scale: usize,
+    out: &mut dyn Write,
+) -> Result<(), ExportError> {
+    let scale = scale.max(1);
+    let (width, height) = (
+        pixels.width().saturating_mul(scale),
+        pixels.height().saturating_mul(scale),
+    );
+    visualize::check_size(width, height)?;
+    match format {
+        Format::Ppm => write_ppm(pixels, scale, out)?,
+        Format::Svg => write_svg(pixels, scale, out)?,
+        Format::Png => write_png(pixels, scale, out)?,
+    }
+    Ok(())
+}
+
+/// Rows of RGB bytes, repeating each cell `scale` times in both directions.
+fn scaled_rows(pixels: &Grid<Rgb>, scale: usize) -> impl Iterator<Item = Vec<u8>> + '_ {
+    pixels.rows().flat_map(move |row| {
+        let bytes = row
+            .iter()
+            .flat_map(|&Rgb(r, g, b)| [r, g, b].repeat(scale))
+            .collect::<Vec<_>>();
+        std::iter::repeat(bytes).take(scale)
+    })
+}
+
+fn write_ppm(pixels: &Grid<Rgb>, scale: usize, out: &mut dyn Write) -> io::Result<()> {
+    write!(
+        out,
+        "P6\n{} {}\n255\n",
+        pixels.width() * scale,
+        pixels.height() * scale
+    )?;
+    for row in scaled_rows(pixels, scale) {
+        out.write_all(&row)?;
+    }
+    Ok(())
+}
+
+fn write_svg(pixels: &Grid<Rgb>, scale: usize, out: &mut dyn Write) -> io::Result<()> {
+    let (width, height) = (pixels.width(), pixels.height());
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" shape-rendering="crispEdges">"#,
+        width * scale,
+        height * scale,
+        width,
+        height
+    )?;
+    for (i, row) in pixels.rows().enumerate() {
+        let mut j = 0;
+        while j < row.len() {
+            let Rgb(r, g, b) = row[j];
+            let run = row[j..].iter().take_while(|&&c| c == row[j]).count();
+            writeln!(
+                out,
+                r##"<rect x="{j}" y="{i}" width="{run}" height="1" fill="#{r:02x}{g:02x}{b:02x}"/>"##
+            )?;
+            j += run;
+        }
+    }
+    writeln!(out, "</svg>")
+}
+
+#[cfg(feature = "png")]
+fn write_png(pixels: &Grid<Rgb>, scale: usize, out: &mut dyn Write) -> Result<(), ExportError> {
+    let size =
+        |n: usize| u32::try_from(n * scale).map_err(|_| TooLarge(pixels.width(), pixels.height()));
+    let mut encoder = png::Encoder::new(out, size(pixels.width())?, size(pixels.height())?);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&scaled_rows(pixels, scale).flatten().collect::<Vec<_>>())?;
+    writer.finish()?;
+    Ok(())
+}
+
+#[cfg(not(feature = "png"))]
+fn write_png(_: &Grid<Rgb>, _: usize, _: &mut dyn Write) -> Result<(), ExportError> {
+    Err(ExportError::PngDisabled)
+}
+
+/// Solution with a picture of its result.
+pub trait Export: Solution {
+    fn image(input: Self::Input) -> Result<Grid<Rgb>>;
+}
+
+/// Steps `renderer` to the end, for days whose last frame is the picture.
+#[must_use]
+pub fn last_frame(mut renderer: Box<dyn Renderer>) -> Grid<Rgb> {
+    while renderer.step() {}
+    renderer.frame().pixels
+}
+
+/// Color on a heat map from blue at `t = 0`, through orange, to red at `t = 1`.
+#[must_use]
+pub fn heat(t: f64) -> Rgb {
+    if t < 0.5 {
+        Rgb(40, 90, 170).mix(Rgb(250, 170, 40), t * 2.0)
+    } else {
+        Rgb(250, 170, 40).mix(Rgb(255, 60, 30), t * 2.0 - 1.0)
+    }
+}
+
+/// Where `n` is between `min` and `max`, 0 if they're the same.
+#[must_use]
+pub fn fraction(n: u32, min: u32, max: u32) -> f64 {
+    if max > min {
+        f64::from(n.saturating_sub(min)) / f64::from(max - min)
+    } else {
+        0.0
+    }
+}
+
+type ImageFn = fn(&str) -> Result<Grid<Rgb>>;
+
+fn image_of<S: Export>(input: &str) -> Result<Grid<Rgb>> {
+    S::image(S::parse(input)?)
+}
+
+/// Days implementing [`Export`], ordered by day.
+static IMAGES: &[(u8, ImageFn)] = &[
+    (5, image_of::<Day05>),
+    (9, image_of::<Day09>),
+    (11, image_of::<Day11>),
+    (13, image_of::<Day13>),
+];
+
+/// Days with an image.
+pub fn days() -> impl Iterator<Item = u8> {
+    IMAGES.iter().map(|&(day, _)| day)
+}
+
+/// Parses `input` and draws the image of `day`, `None` if the day has no image.
+#[must_use]
+pub fn image(day: u8, input: &str) -> Option<Result<Grid<Rgb>>> {
+    IMAGES
+        .iter()
+        .find(|&&(d, _)| d == day)
+        .map(|(_, f)| f(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Grid<Rgb> {
+        Grid::from_fn(3, 2, |(i, j)| {
+            if j == 2 || (i + j) % 2 == 0 {
+                Rgb(255, 0, 0)
+            } else {
+                Rgb::BLACK
+            }
+        })
+    }
+
+    fn export(format: Format, scale: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+        write(&checkerboard(), format, scale, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_ppm() {
+        let ppm = export(Format::Ppm, 2);
+        let header = b"P6\n6 4\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        let body = &ppm[header.len()..];
+        assert_eq!(body.len(), 6 * 4 * 3);
+        // Second row of the first cell repeats the first.
+        assert_eq!(&body[..18], &body[18..36]);
+        assert_eq!(&body[..12], &[255, 0, 0, 255, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = String::from_utf8(export(Format::Svg, 10)).unwrap();
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="30" height="20" viewBox="0 0 3 2""#
+        ));
+        assert!(svg.contains(r##"<rect x="1" y="1" width="2" height="1" fill="#ff0000"/>"##));
+        assert_eq!(svg.matches("<rect").count(), 5);
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_png() {
+        let mut out = Vec::new();
+        let result = write(&checkerboard(), Format::Png, 1, &mut out);
+        if cfg!(feature = "png") {
+            assert!(result.is_ok());
+            assert_eq!(&out[1..4], b"PNG");
+        } else {
+            assert!(matches!(result, Err(ExportError::PngDisabled)));
+        }
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(Format::from_path(Path::new("a/b.SVG")), Ok(Format::Svg));
+        assert_eq!(
+            Format::from_path(Path::new("b")),
+            Err(UnknownFormat(String::new()))
+        );
+        assert!(matches!(
+            write(&checkerboard(), Format::Ppm, 1 << 12, &mut io::sink()),
+            Err(ExportError::TooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn test_images() {
+        let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+        for day in days() {
+            let path = examples.join(format!("day{day:02}/example.txt"));
+            let input = std::fs::read_to_string(&path).unwrap();
+            let pixels = image(day, &input).unwrap().unwrap();
+            let size = match day {
+                9 => (10, 5),
+                13 => (5, 5),
+                _ => (10, 10),
+            };
+            assert_eq!((pixels.width(), pixels.height()), size, "day {day}");
+        }
+        assert!(image(1, "").is_none());
+    }
+}
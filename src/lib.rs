@@ -3,8 +3,19 @@
 #![deny(clippy::pedantic)]
 #![allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
 
+use std::{fmt, io::BufRead, path::PathBuf};
+
 pub type Result<T, E = Box<dyn std::error::Error>> = std::result::Result<T, E>;
 
+pub mod generate;
+pub mod grid;
+pub mod image_export;
+pub mod ocr;
+pub mod overflow;
+pub mod par;
+pub mod parse;
+pub mod visualize;
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -18,3 +29,259 @@ pub mod day10;
 pub mod day11;
 pub mod day12;
 pub mod day13;
+
+/// Solution for a day's puzzle.
+///
+/// The input is parsed once and shared by both parts.
+pub trait Solution {
+    type Input;
+    type Part1: Into<Answer>;
+    type Part2: Into<Answer>;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1>;
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2>;
+
+    /// Parses `input` and solves part 1.
+    fn solve_part1(input: &str) -> Result<Self::Part1> {
+        Self::part1(&Self::parse(input)?)
+    }
+
+    /// Parses `input` and solves part 2.
+    fn solve_part2(input: &str) -> Result<Self::Part2> {
+        Self::part2(&Self::parse(input)?)
+    }
+}
+
+/// Solution that can also be solved from a reader, one line at a time.
+///
+/// The memory used doesn't grow with the length of the lines read, so this works
+/// for generated inputs too large to read into a string.
+pub trait Streaming: Solution {
+    fn stream_part1(input: &mut dyn BufRead) -> Result<Self::Part1>;
+
+    fn stream_part2(input: &mut dyn BufRead) -> Result<Self::Part2>;
+}
+
+/// Answer to a part of a puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Number(u64),
+    Signed(i64),
+    Text(String),
+    Letters(ocr::Letters),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{n}"),
+            Answer::Signed(n) => write!(f, "{n}"),
+            Answer::Text(s) => f.write_str(s),
+            Answer::Letters(l) => l.fmt(f),
+        }
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Self {
+        Answer::Number(n.into())
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Self {
+        Answer::Number(n)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Number(n as u64)
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Self {
+        Answer::Signed(n.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Signed(n)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<ocr::Letters> for Answer {
+    fn from(l: ocr::Letters) -> Self {
+        Answer::Letters(l)
+    }
+}
+
+/// Parsed input of some day, ready to be solved.
+pub trait Parsed {
+    fn part1(&self) -> Result<Answer>;
+
+    fn part2(&self) -> Result<Answer>;
+}
+
+struct ParsedInput<S: Solution>(S::Input);
+
+impl<S: Solution> Parsed for ParsedInput<S> {
+    fn part1(&self) -> Result<Answer> {
+        S::part1(&self.0).map(Into::into)
+    }
+
+    fn part2(&self) -> Result<Answer> {
+        S::part2(&self.0).map(Into::into)
+    }
+}
+
+fn parse_boxed<S>(input: &str) -> Result<Box<dyn Parsed>>
+where
+    S: Solution + 'static,
+{
+    Ok(Box::new(ParsedInput::<S>(S::parse(input)?)))
+}
+
+fn stream_part1<S: Streaming>(input: &mut dyn BufRead) -> Result<Answer> {
+    S::stream_part1(input).map(Into::into)
+}
+
+fn stream_part2<S: Streaming>(input: &mut dyn BufRead) -> Result<Answer> {
+    S::stream_part2(input).map(Into::into)
+}
+
+type StreamFn = fn(&mut dyn BufRead) -> Result<Answer>;
+
+/// Entry in the registry of solutions.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub day: u8,
+    parse: fn(&str) -> Result<Box<dyn Parsed>>,
+    stream: Option<[StreamFn; 2]>,
+}
+
+impl Day {
+    /// Name of the day, e.g., `day01`.
+    #[must_use]
+    pub fn name(&self) -> String {
+        format!("day{:02}", self.day)
+    }
+
+    /// Path to the puzzle input, e.g., `inputs/day01.txt`.
+    #[must_use]
+    pub fn input_path(&self) -> PathBuf {
+        PathBuf::from("inputs").join(format!("{}.txt", self.name()))
+    }
+
+    /// Parses `input` with this day's parser.
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Parsed>> {
+        (self.parse)(input)
+    }
+
+    /// Returns `true` if this day implements [`Streaming`].
+    #[must_use]
+    pub fn can_stream(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Solves part 1 reading `input` one line at a time.
+    pub fn stream_part1(&self, input: &mut dyn BufRead) -> Result<Answer> {
+        self.stream_fn(0)?(input)
+    }
+
+    /// Solves part 2 reading `input` one line at a time.
+    pub fn stream_part2(&self, input: &mut dyn BufRead) -> Result<Answer> {
+        self.stream_fn(1)?(input)
+    }
+
+    fn stream_fn(&self, part: usize) -> Result<StreamFn> {
+        self.stream
+            .map(|fs| fs[part])
+            .ok_or_else(|| format!("{} can't read its input as a stream", self.name()).into())
+    }
+}
+
+impl fmt::Debug for Day {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Day")
+            .field("day", &self.day)
+            .field("can_stream", &self.can_stream())
+            .finish_non_exhaustive()
+    }
+}
+
+macro_rules! days {
+    ($($day:literal => $solution:ty $([$stream:ident])?),* $(,)?) => {
+        &[$(Day {
+            day: $day,
+            parse: parse_boxed::<$solution>,
+            stream: days!(@stream $solution $(, $stream)?),
+        }),*]
+    };
+    (@stream $solution:ty) => { None };
+    (@stream $solution:ty, stream) => {
+        Some([stream_part1::<$solution>, stream_part2::<$solution>])
+    };
+}
+
+/// All solutions, ordered by day.
+///
+/// Days marked with `[stream]` implement [`Streaming`].
+pub static DAYS: &[Day] = days![
+    1 => day01::Day01 [stream],
+    2 => day02::Day02 [stream],
+    3 => day03::Day03,
+    4 => day04::Day04,
+    5 => day05::Day05 [stream],
+    6 => day06::Day06,
+    7 => day07::Day07,
+    8 => day08::Day08,
+    9 => day09::Day09,
+    10 => day10::Day10 [stream],
+    11 => day11::Day11,
+    12 => day12::Day12,
+    13 => day13::Day13,
+];
+
+/// Finds the solution for `day`.
+#[must_use]
+pub fn get_day(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days() {
+        assert!(DAYS.windows(2).all(|w| w[0].day < w[1].day));
+
+        let parsed = get_day(7)
+            .unwrap()
+            .parse("16,1,2,0,4,2,7,1,2,14\n")
+            .unwrap();
+        assert_eq!(parsed.part1().unwrap(), Answer::Number(37));
+        assert_eq!(parsed.part2().unwrap().to_string(), "168");
+
+        let day = get_day(1).unwrap();
+        assert!(day.can_stream());
+        let mut input = "1\n3\n2\n4\n".as_bytes();
+        assert_eq!(day.stream_part1(&mut input).unwrap(), Answer::Number(2));
+        assert!(get_day(7)
+            .unwrap()
+            .stream_part1(&mut "".as_bytes())
+            .is_err());
+    }
+}
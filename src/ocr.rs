@@ -0,0 +1,141 @@
+//! Recognizes letters drawn with `#` and `.` in the 4x6 font used by the puzzles.
+
+use std::fmt;
+
+const HEIGHT: usize = 6;
+const WIDTH: usize = 4;
+// Letters are separated by an empty column.
+const STRIDE: usize = WIDTH + 1;
+
+const FONT: &[(char, [&str; HEIGHT])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Reads the letters in `picture`, one row per line.
+///
+/// Unknown glyphs are read as `?`.
+/// Returns `None` if the picture is not 6 rows high.
+#[must_use]
+pub fn recognize(picture: &str) -> Option<String> {
+    let rows = picture
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#').collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    if rows.len() != HEIGHT {
+        return None;
+    }
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |i: usize, j: usize| rows[i].get(j).copied().unwrap_or(false);
+    let letters = (0..(width + WIDTH) / STRIDE)
+        .map(|n| {
+            let x = n * STRIDE;
+            FONT.iter()
+                .find(|(_, glyph)| {
+                    glyph.iter().enumerate().all(|(i, row)| {
+                        row.chars()
+                            .enumerate()
+                            .all(|(j, c)| (c == '#') == lit(i, x + j))
+                    })
+                })
+                .map_or('?', |&(c, _)| c)
+        })
+        .collect();
+    Some(letters)
+}
+
+/// Draws `text` in the font, one row per line, with `None` for letters not in the font.
+#[must_use]
+pub fn draw(text: &str) -> Option<String> {
+    let glyphs = text
+        .chars()
+        .map(|c| FONT.iter().find(|&&(f, _)| f == c).map(|(_, glyph)| glyph))
+        .collect::<Option<Vec<_>>>()?;
+    let rows = (0..HEIGHT)
+        .map(|i| {
+            glyphs
+                .iter()
+                .map(|glyph| glyph[i])
+                .collect::<Vec<_>>()
+                .join(".")
+        })
+        .collect::<Vec<_>>();
+    Some(rows.join("\n"))
+}
+
+/// Letters in the font, e.g., to generate pictures.
+pub fn alphabet() -> impl Iterator<Item = char> {
+    FONT.iter().map(|&(c, _)| c)
+}
+
+/// Letters drawn in a picture, e.g., the answer to day 13 part 2.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Letters {
+    /// Recognized letters, or the picture itself if it couldn't be read.
+    pub text: String,
+    pub picture: String,
+}
+
+impl Letters {
+    #[must_use]
+    pub fn from_picture(picture: String) -> Self {
+        let text = recognize(&picture).unwrap_or_else(|| picture.clone());
+        Self { text, picture }
+    }
+}
+
+impl fmt::Display for Letters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recognize() {
+        let picture = indoc::indoc! {"
+            #..#.###...##.
+            #..#.#..#.#..#
+            ####.###..#...
+            #..#.#..#.#...
+            #..#.#..#.#..#
+            #..#.###...##.
+        "};
+        assert_eq!(recognize(picture).as_deref(), Some("HBC"));
+        assert_eq!(draw("HBC").as_deref(), Some(picture.trim_end()));
+
+        let text = alphabet().collect::<String>();
+        assert_eq!(recognize(&draw(&text).unwrap()), Some(text));
+        assert_eq!(draw("HQ"), None);
+    }
+
+    #[test]
+    fn test_unknown() {
+        let picture = "#####\n#...#\n#...#\n#...#\n#####";
+        assert_eq!(recognize(picture), None);
+        let letters = Letters::from_picture(picture.to_owned());
+        assert_eq!(letters.text, picture);
+
+        let picture = "####\n####\n####\n####\n####\n####";
+        assert_eq!(recognize(picture).as_deref(), Some("?"));
+    }
+}
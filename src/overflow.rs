@@ -0,0 +1,8 @@
+use thiserror::Error;
+
+/// The answer doesn't fit in its type, returned instead of a wrapped, wrong answer.
+///
+/// Names the operation that overflowed, e.g., `Overflow("horizontal position * depth")`.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+#[error("overflow computing {0}")]
+pub struct Overflow(pub &'static str);
@@ -0,0 +1,41 @@
+//! Loops over independent items, on every core with the `parallel` feature.
+//!
+//! The results are in the order of the items either way, so answers and errors are the
+//! same as without the feature.
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// `items.iter().map(f).collect()`.
+pub fn map<T: Sync, U: Send>(items: &[T], f: impl Fn(&T) -> U + Sync + Send) -> Vec<U> {
+    #[cfg(feature = "parallel")]
+    return items.par_iter().map(f).collect();
+    #[cfg(not(feature = "parallel"))]
+    return items.iter().map(f).collect();
+}
+
+/// `items.iter().map(f).sum()`, for sums that don't depend on the order.
+pub fn sum<T: Sync, S>(items: &[T], f: impl Fn(&T) -> S + Sync + Send) -> S
+where
+    S: Send + std::iter::Sum,
+{
+    #[cfg(feature = "parallel")]
+    return items.par_iter().map(f).sum();
+    #[cfg(not(feature = "parallel"))]
+    return items.iter().map(f).sum();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_order() {
+        let items = (0..10_000u64).collect::<Vec<_>>();
+        assert_eq!(
+            map(&items, |&i| i * 2),
+            items.iter().map(|&i| i * 2).collect::<Vec<_>>()
+        );
+        assert_eq!(sum(&items, |&i| u128::from(i)), 49_995_000);
+    }
+}
@@ -0,0 +1,194 @@
+//! Parse errors located in the puzzle input.
+
+use std::{error::Error, fmt, io::BufRead};
+
+type Source = Box<dyn Error + Send + Sync>;
+
+/// Error at some position of the input.
+///
+/// Errors are created relative to the string being parsed, then moved with
+/// [`ParseError::within`] as they propagate to the whole input.
+#[derive(Debug)]
+pub struct ParseError {
+    /// Line number starting from 1.
+    pub line: usize,
+    /// Column number in characters starting from 1.
+    pub column: usize,
+    /// The line containing the error.
+    pub text: String,
+    offset: usize,
+    source: Source,
+}
+
+impl ParseError {
+    /// Error in `s` at the start of `at`, a slice of `s`.
+    pub fn new(s: &str, at: &str, source: impl Into<Source>) -> Self {
+        Self::at_offset(s, offset_of(s, at), source.into())
+    }
+
+    /// Error at the end of `s`, e.g., for missing parts.
+    pub fn at_end(s: &str, source: impl Into<Source>) -> Self {
+        Self::at_offset(s, s.len(), source.into())
+    }
+
+    /// Moves the location of this error in `inner`, a slice of `outer`, to be relative to `outer`.
+    #[must_use]
+    pub fn within(self, outer: &str, inner: &str) -> Self {
+        Self::at_offset(outer, offset_of(outer, inner) + self.offset, self.source)
+    }
+
+    /// Moves this error in a single line to be on line `line` of the input.
+    #[must_use]
+    pub fn on_line(self, line: usize) -> Self {
+        Self {
+            line: self.line + line - 1,
+            ..self
+        }
+    }
+
+    fn at_offset(s: &str, offset: usize, source: Source) -> Self {
+        let before = &s[..offset];
+        let start = before.rfind('\n').map_or(0, |i| i + 1);
+        let end = s[offset..].find('\n').map_or(s.len(), |i| offset + i);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: s[start..offset].chars().count() + 1,
+            text: s[start..end].trim_end_matches('\r').to_owned(),
+            offset,
+            source,
+        }
+    }
+
+    /// The underlying error.
+    #[must_use]
+    pub fn kind(&self) -> &(dyn Error + Send + Sync + 'static) {
+        &*self.source
+    }
+}
+
+fn offset_of(s: &str, at: &str) -> usize {
+    let offset = (at.as_ptr() as usize).wrapping_sub(s.as_ptr() as usize);
+    assert!(offset <= s.len(), "`at` must be a slice of `s`");
+    offset
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {} ({:?}): {}",
+            self.line, self.column, self.text, self.source
+        )?;
+        let mut source = self.source.source();
+        while let Some(err) = source {
+            write!(f, ": {err}")?;
+            source = err.source();
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses each line of `input` with `f`, locating errors in `input`.
+pub fn lines<'a, T>(
+    input: &'a str,
+    mut f: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .map(|line| f(line).map_err(|e| e.within(input, line)))
+        .collect()
+}
+
+/// Parses each line read from `reader` with `f`, like [`lines`] without reading the whole input.
+///
+/// Only the current line is kept in memory.
+pub fn read_lines<'r, T>(
+    reader: &'r mut dyn BufRead,
+    mut f: impl FnMut(&str) -> Result<T, ParseError> + 'r,
+) -> impl Iterator<Item = crate::Result<T>> + 'r {
+    let mut buf = String::new();
+    let mut line = 0;
+    std::iter::from_fn(move || {
+        buf.clear();
+        match reader.read_line(&mut buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                line += 1;
+                let s = buf
+                    .strip_suffix('\n')
+                    .map_or(buf.as_str(), |s| s.strip_suffix('\r').unwrap_or(s));
+                Some(f(s).map_err(|e| e.on_line(line).into()))
+            }
+            Err(e) => Some(Err(e.into())),
+        }
+    })
+}
+
+/// Calls `f` with the items of `results` up to the first error, which is returned instead.
+pub fn until_error<T, U>(
+    results: impl Iterator<Item = crate::Result<T>>,
+    f: impl FnOnce(&mut dyn Iterator<Item = T>) -> U,
+) -> crate::Result<U> {
+    let mut error = None;
+    let value = {
+        let mut results = results;
+        let mut items = std::iter::from_fn(|| match results.next()? {
+            Ok(item) => Some(item),
+            Err(e) => {
+                error = Some(e);
+                None
+            }
+        });
+        f(&mut items)
+    };
+    error.map_or(Ok(value), Err)
+}
+
+/// Parses each item of `input` separated by `sep` with `str::parse`.
+pub fn separated<T>(input: &str, sep: char) -> Result<Vec<T>, ParseError>
+where
+    T: std::str::FromStr,
+    T::Err: Into<Source>,
+{
+    input
+        .split(sep)
+        .map(|s| s.parse().map_err(|e| ParseError::new(input, s, e)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location() {
+        let input = "1,2\n3,x4\n";
+        let err = lines(input, |line| separated::<u32>(line, ',')).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.text, "3,x4");
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3 (\"3,x4\"): invalid digit found in string"
+        );
+
+        let err = ParseError::at_end(input, "missing").within(input, input);
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 1, ""));
+    }
+
+    #[test]
+    fn test_read_lines() {
+        let mut reader = "1,2\r\n3\n4,x\n".as_bytes();
+        let sums = read_lines(&mut reader, |line| {
+            Ok(separated::<u32>(line, ',')?.iter().sum::<u32>())
+        });
+        let mut seen = Vec::new();
+        let err = until_error(sums, |sums| seen.extend(sums)).unwrap_err();
+        assert_eq!(seen, [3, 3]);
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 3 (\"4,x\"): invalid digit found in string"
+        );
+    }
+}
@@ -0,0 +1,397 @@
+//! Animations of how some days are solved, drawn in a terminal with ANSI escapes.
+//!
+//! A [`Renderer`] draws the state of a day as a [`Frame`] and steps through the solution,
+//! [`animate`] plays the frames at a frame rate and can be paused and stepped.
+
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    sync::mpsc::{Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use thiserror::Error;
+
+use crate::{day05::Day05, day09::Day09, day11::Day11, day13::Day13, grid::Grid, Result, Solution};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+
+    /// Color between `self` at `t = 0` and `other` at `t = 1`.
+    #[must_use]
+    pub fn mix(self, other: Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let channel =
+            |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * t).round() as u8;
+        Rgb(
+            channel(self.0, other.0),
+            channel(self.1, other.1),
+            channel(self.2, other.2),
+        )
+    }
+}
+
+const PALETTE: [Rgb; 8] = [
+    Rgb(230, 25, 75),
+    Rgb(60, 180, 75),
+    Rgb(255, 225, 25),
+    Rgb(67, 99, 216),
+    Rgb(245, 130, 49),
+    Rgb(145, 30, 180),
+    Rgb(66, 212, 244),
+    Rgb(240, 50, 230),
+];
+
+/// One of a few colors easy to tell apart, repeating after 8.
+#[must_use]
+pub fn palette(i: usize) -> Rgb {
+    PALETTE[i % PALETTE.len()]
+}
+
+/// Picture of the state of a day, with a line describing it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    pub pixels: Grid<Rgb>,
+    pub caption: String,
+}
+
+impl Frame {
+    /// Shrinks the frame to at most `width` by `height` pixels, averaging the pixels merged.
+    #[must_use]
+    pub fn fit(&self, width: usize, height: usize) -> Frame {
+        let (w, h) = (self.pixels.width(), self.pixels.height());
+        let scale = ceil_div(w, width.max(1)).max(ceil_div(h, height.max(1)));
+        if scale <= 1 {
+            return self.clone();
+        }
+        let pixels = Grid::from_fn(ceil_div(w, scale), ceil_div(h, scale), |(i, j)| {
+            let block = (i * scale..h.min((i + 1) * scale))
+                .flat_map(|y| (j * scale..w.min((j + 1) * scale)).map(move |x| (y, x)))
+                .map(|p| self.pixels[p])
+                .collect::<Vec<_>>();
+            let average = |c: fn(&Rgb) -> u8| {
+                let sum = block.iter().map(|p| usize::from(c(p))).sum::<usize>();
+                u8::try_from(sum / block.len()).unwrap_or(u8::MAX)
+            };
+            Rgb(average(|p| p.0), average(|p| p.1), average(|p| p.2))
+        });
+        Frame {
+            pixels,
+            caption: self.caption.clone(),
+        }
+    }
+
+    /// Draws the pixels with 24-bit colors, two rows per line with half blocks.
+    #[must_use]
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        for i in (0..self.pixels.height()).step_by(2) {
+            for j in 0..self.pixels.width() {
+                let Rgb(r, g, b) = self.pixels[(i, j)];
+                let Rgb(br, bg, bb) = self.pixels.get((i + 1, j)).copied().unwrap_or_default();
+                let _ = write!(
+                    out,
+                    "\x1b[38;2;{r};{g};{b}m\x1b[48;2;{br};{bg};{bb}m\u{2580}"
+                );
+            }
+            out.push_str("\x1b[0m\n");
+        }
+        out
+    }
+}
+
+// Much larger than any terminal, or any puzzle input.
+const MAX_PIXELS: usize = 1 << 24;
+
+#[derive(Debug, Error)]
+#[error("{0}x{1} pixels is too large to draw")]
+pub struct TooLarge(pub usize, pub usize);
+
+/// Checks that a frame of `width` by `height` pixels can be drawn.
+pub fn check_size(width: usize, height: usize) -> Result<(), TooLarge> {
+    if width.saturating_mul(height) > MAX_PIXELS {
+        Err(TooLarge(width, height))
+    } else {
+        Ok(())
+    }
+}
+
+fn ceil_div(a: usize, b: usize) -> usize {
+    (a + b - 1) / b
+}
+
+/// Draws the state of a day while stepping through the solution.
+pub trait Renderer {
+    /// The current state.
+    fn frame(&self) -> Frame;
+
+    /// Advances to the next state, returns `false` if there's nothing left to show.
+    fn step(&mut self) -> bool;
+}
+
+/// Solution that can show how it's solved.
+pub trait Visualize: Solution {
+    fn renderer(input: Self::Input) -> Result<Box<dyn Renderer>>;
+}
+
+type RendererFn = fn(&str) -> Result<Box<dyn Renderer>>;
+
+fn renderer_boxed<S: Visualize>(input: &str) -> Result<Box<dyn Renderer>> {
+    S::renderer(S::parse(input)?)
+}
+
+/// Days implementing [`Visualize`], ordered by day.
+static RENDERERS: &[(u8, RendererFn)] = &[
+    (5, renderer_boxed::<Day05>),
+    (9, renderer_boxed::<Day09>),
+    (11, renderer_boxed::<Day11>),
+    (13, renderer_boxed::<Day13>),
+];
+
+/// Days that can be visualized.
+pub fn days() -> impl Iterator<Item = u8> {
+    RENDERERS.iter().map(|&(day, _)| day)
+}
+
+/// Parses `input` and creates the renderer for `day`, `None` if the day can't be visualized.
+#[must_use]
+pub fn renderer(day: u8, input: &str) -> Option<Result<Box<dyn Renderer>>> {
+    RENDERERS
+        .iter()
+        .find(|&&(d, _)| d == day)
+        .map(|(_, f)| f(input))
+}
+
+/// Changes to a running animation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    /// Pauses or resumes.
+    Pause,
+    /// Shows the next frame while paused.
+    Step,
+    Faster,
+    Slower,
+    Quit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// Frames per second.
+    pub fps: u32,
+    /// Starts paused, waiting for [`Control::Step`].
+    pub paused: bool,
+    /// Size of the terminal in characters.
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            fps: 10,
+            paused: false,
+            width: 80,
+            height: 24,
+        }
+    }
+}
+
+const MAX_FPS: u32 = 120;
+
+/// Plays the frames of `renderer` to `out` until the last one or [`Control::Quit`].
+///
+/// Returns the number of frames drawn. Frames are shrunk to fit the terminal, keeping a
+/// line for the caption and one for the status.
+pub fn animate(
+    renderer: &mut dyn Renderer,
+    opts: &Options,
+    controls: &Receiver<Control>,
+    out: &mut dyn Write,
+) -> io::Result<usize> {
+    // Clear the screen and hide the cursor, showing it again even if drawing fails.
+    write!(out, "\x1b[2J\x1b[?25l")?;
+    let result = play(renderer, opts, controls, out);
+    let shown = write!(out, "\x1b[?25h").and_then(|()| out.flush());
+    result.and_then(|frames| shown.map(|()| frames))
+}
+
+fn play(
+    renderer: &mut dyn Renderer,
+    opts: &Options,
+    controls: &Receiver<Control>,
+    out: &mut dyn Write,
+) -> io::Result<usize> {
+    let mut fps = opts.fps.clamp(1, MAX_FPS);
+    let mut paused = opts.paused;
+    let mut frames = 0;
+    loop {
+        let frame = renderer
+            .frame()
+            .fit(opts.width, opts.height.saturating_sub(2) * 2);
+        let status = if paused {
+            "paused: Enter for the next frame, p to resume, q to quit"
+        } else {
+            "p to pause, + or - to change the speed, q to quit (then Enter)"
+        };
+        write!(
+            out,
+            "\x1b[H\x1b[0J{}{}\n{} fps, {}\n",
+            frame.to_ansi(),
+            frame.caption,
+            fps,
+            status
+        )?;
+        out.flush()?;
+        frames += 1;
+
+        let interval = Duration::from_secs_f64(1.0 / f64::from(fps));
+        let control = if paused {
+            // Nothing can resume the animation once the controls are gone.
+            controls.recv().unwrap_or(Control::Quit)
+        } else {
+            match controls.recv_timeout(interval) {
+                Ok(control) => control,
+                Err(RecvTimeoutError::Timeout) => Control::Step,
+                Err(RecvTimeoutError::Disconnected) => {
+                    thread::sleep(interval);
+                    Control::Step
+                }
+            }
+        };
+        match control {
+            Control::Step => {
+                if !renderer.step() {
+                    return Ok(frames);
+                }
+            }
+            Control::Pause => paused = !paused,
+            Control::Faster => fps = (fps * 2).min(MAX_FPS),
+            Control::Slower => fps = (fps / 2).max(1),
+            Control::Quit => return Ok(frames),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    struct Counter {
+        n: usize,
+        last: usize,
+    }
+
+    impl Renderer for Counter {
+        fn frame(&self) -> Frame {
+            Frame {
+                pixels: Grid::from_fn(3, 3, |(i, j)| {
+                    if i * 3 + j < self.n {
+                        Rgb(255, 255, 255)
+                    } else {
+                        Rgb::BLACK
+                    }
+                }),
+                caption: format!("frame {}", self.n),
+            }
+        }
+
+        fn step(&mut self) -> bool {
+            if self.n == self.last {
+                return false;
+            }
+            self.n += 1;
+            true
+        }
+    }
+
+    fn play(opts: &Options, controls: &[Control]) -> (usize, String) {
+        let (tx, rx) = mpsc::channel();
+        for &control in controls {
+            tx.send(control).unwrap();
+        }
+        drop(tx);
+        let mut out = Vec::new();
+        let mut renderer = Counter { n: 0, last: 3 };
+        let frames = animate(&mut renderer, opts, &rx, &mut out).unwrap();
+        (frames, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_animate() {
+        let opts = Options {
+            fps: MAX_FPS,
+            ..Options::default()
+        };
+        let (frames, out) = play(&opts, &[]);
+        assert_eq!(frames, 4);
+        assert!(out.contains("frame 3\n"));
+        assert!(out.ends_with("\x1b[?25h"));
+
+        let paused = Options {
+            paused: true,
+            ..opts
+        };
+        let (frames, out) = play(&paused, &[Control::Step, Control::Step, Control::Quit]);
+        assert_eq!(frames, 3);
+        assert!(out.contains("frame 2\n") && !out.contains("frame 3\n"));
+
+        // Resuming plays to the end, the controls stop after the last frame.
+        let (frames, _) = play(&paused, &[Control::Pause, Control::Faster]);
+        assert_eq!(frames, 6);
+    }
+
+    #[test]
+    fn test_renderers() {
+        let examples = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+        for day in days() {
+            let path = examples.join(format!("day{day:02}/example.txt"));
+            let input = std::fs::read_to_string(&path).unwrap();
+            let mut renderer = renderer(day, &input).unwrap().unwrap();
+            let mut steps = 0;
+            while renderer.step() {
+                steps += 1;
+                assert!(steps < 1000, "day {day} doesn't end");
+            }
+            let last = renderer.frame();
+            match day {
+                // Until all octopuses flash at once.
+                11 => assert_eq!(steps, 195),
+                13 => {
+                    let lit = last
+                        .pixels
+                        .iter()
+                        .filter(|&&p| p != last.pixels[(1, 1)])
+                        .count();
+                    assert_eq!((last.pixels.width(), last.pixels.height(), lit), (5, 5, 16));
+                }
+                _ => assert!(steps > 0),
+            }
+        }
+        assert!(renderer(1, "").is_none());
+    }
+
+    #[test]
+    fn test_frame() {
+        let frame = Frame {
+            pixels: Grid::from_fn(4, 3, |(i, _)| Rgb(u8::try_from(i).unwrap() * 100, 0, 0)),
+            caption: String::new(),
+        };
+        let ansi = frame.to_ansi();
+        assert_eq!(ansi.lines().count(), 2);
+        assert!(ansi.starts_with("\x1b[38;2;0;0;0m\x1b[48;2;100;0;0m\u{2580}"));
+        // The last row has nothing below it.
+        assert!(ansi.contains("\x1b[38;2;200;0;0m\x1b[48;2;0;0;0m\u{2580}"));
+
+        assert_eq!(frame.fit(4, 3), frame);
+        let small = frame.fit(2, 2);
+        assert_eq!((small.pixels.width(), small.pixels.height()), (2, 2));
+        assert_eq!(small.pixels[(0, 0)], Rgb(50, 0, 0));
+        assert_eq!(small.pixels[(1, 1)], Rgb(200, 0, 0));
+    }
+}
@@ -0,0 +1,115 @@
+//! Runs every example in `examples/dayNN/<name>.txt` and compares the answers
+//! with the expected ones in `examples/dayNN/<name>.toml`.
+//! Days that can read their input as a stream are checked reading it both ways.
+//!
+//! The expected answers are written like in `answers.toml`, either part can be left out:
+//!
+//! ```toml
+//! part1 = 7
+//! part2 = '''
+//! #####
+//! #####
+//! '''
+//! ```
+
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+struct Expected {
+    part1: Option<toml::Value>,
+    part2: Option<toml::Value>,
+}
+
+fn expected_to_string(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.trim_end().to_owned(),
+        v => v.to_string(),
+    }
+}
+
+/// Runs the example at `path`, returning the number of checked answers.
+fn run_example(day: u8, path: &Path) -> Result<usize, String> {
+    let solution = aoc2021::get_day(day).ok_or_else(|| format!("no solution for day {}", day))?;
+    let input = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let answers = path.with_extension("toml");
+    let expected: Expected = fs::read_to_string(&answers)
+        .map_err(|e| format!("{}: {}", answers.display(), e))
+        .and_then(|s| toml::from_str(&s).map_err(|e| format!("{}: {}", answers.display(), e)))?;
+
+    let parsed = solution.parse(&input).map_err(|e| e.to_string())?;
+    let mut parts = vec![
+        ("part 1", expected.part1.as_ref(), parsed.part1()),
+        ("part 2", expected.part2.as_ref(), parsed.part2()),
+    ];
+    if solution.can_stream() {
+        parts.push((
+            "part 1 (stream)",
+            expected.part1.as_ref(),
+            solution.stream_part1(&mut input.as_bytes()),
+        ));
+        parts.push((
+            "part 2 (stream)",
+            expected.part2.as_ref(),
+            solution.stream_part2(&mut input.as_bytes()),
+        ));
+    }
+
+    let mut checked = 0;
+    for (part, expected, actual) in parts {
+        let expected = match expected {
+            Some(v) => expected_to_string(v),
+            None => continue,
+        };
+        let actual = actual.map_err(|e| format!("{}: {}", part, e))?.to_string();
+        if actual.trim_end() != expected {
+            return Err(format!(
+                "{}: expected {:?}, got {:?}",
+                part, expected, actual
+            ));
+        }
+        checked += 1;
+    }
+    Ok(checked)
+}
+
+#[test]
+fn test_examples() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+    let mut examples = Vec::new();
+    for dir in fs::read_dir(&root).unwrap() {
+        let dir = dir.unwrap().path();
+        let day = dir
+            .file_name()
+            .and_then(|s| s.to_str()?.strip_prefix("day")?.parse::<u8>().ok());
+        let day = match day {
+            Some(day) => day,
+            None => continue,
+        };
+        for file in fs::read_dir(&dir).unwrap() {
+            let path = file.unwrap().path();
+            if path.extension().is_some_and(|e| e == "txt") {
+                examples.push((day, path));
+            }
+        }
+    }
+    examples.sort();
+    assert!(!examples.is_empty(), "no examples in {}", root.display());
+
+    let mut failures = Vec::new();
+    for (day, path) in &examples {
+        let name = path.strip_prefix(&root).unwrap().display();
+        match run_example(*day, path) {
+            Ok(checked) => println!("{}: {} answer(s) ok", name, checked),
+            Err(err) => failures.push(format!("{}: {}", name, err)),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} of {} examples failed:\n{}",
+        failures.len(),
+        examples.len(),
+        failures.join("\n")
+    );
+}
@@ -0,0 +1,218 @@
+//! Inputs that are not puzzle inputs must be rejected with an error, without panicking.
+//! Answers too large for their type are an `Overflow` error instead of a wrong answer.
+//!
+//! The fuzz targets in `fuzz/` look for more of these, see the README.
+
+use std::{
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+};
+
+use aoc2021::{generate::Rng, get_day};
+
+/// Parses and solves both parts, reading the input as a stream too if the day can.
+/// Returns the error messages, or `Err` if anything panicked.
+fn solve(day: u8, input: &str) -> Result<Vec<String>, String> {
+    let solution = get_day(day).unwrap();
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut errors = Vec::new();
+        match solution.parse(input) {
+            Ok(parsed) => {
+                errors.extend(parsed.part1().err().map(|e| format!("part 1: {}", e)));
+                errors.extend(parsed.part2().err().map(|e| format!("part 2: {}", e)));
+            }
+            Err(e) => errors.push(format!("parse: {}", e)),
+        }
+        if solution.can_stream() {
+            let _ = solution.stream_part1(&mut input.as_bytes());
+            let _ = solution.stream_part2(&mut input.as_bytes());
+        }
+        errors
+    }))
+    .map_err(|e| {
+        e.downcast_ref::<&str>()
+            .map(|s| (*s).to_owned())
+            .or_else(|| e.downcast_ref::<String>().cloned())
+            .unwrap_or_default()
+    })
+}
+
+#[test]
+fn test_rejected() {
+    let cases: &[(u8, &str, &str)] = &[
+        (3, "01\n011\n", "found 3 bits instead of 2"),
+        (3, "01\n2\n", "invalid bit '2'"),
+        (5, "0,0 -> 70000,0\n", "parse: line 1, column 8"),
+        (
+            8,
+            "ab ab ab ab ab ab ab ab ab ab | ab ab ab ab\n",
+            "part 2: no wiring",
+        ),
+        (10, "()\n[]\n", "part 2: no incomplete lines"),
+        (
+            11,
+            "02\n",
+            "part 2: octopuses didn't all flash at once within 10000 steps",
+        ),
+        (12, "start-A\nA-B\nB-end\n", "big caves are connected"),
+        (
+            13,
+            "0,10\n\nfold along y=2\n",
+            "part 1: dot at 0,10 is past the edge",
+        ),
+        (13, "\n\nfold along y=2\n", "part 2: no dots to draw"),
+        (
+            13,
+            "9999999,0\n0,9999999\n\nfold along x=99999999\n",
+            "part 2: paper of 10000000x10000000 is too large",
+        ),
+    ];
+    for &(day, input, expected) in cases {
+        let errors = solve(day, input)
+            .unwrap_or_else(|e| panic!("day {} panicked on {:?}: {}", day, input, e));
+        assert!(
+            errors.iter().any(|e| e.contains(expected)),
+            "day {} {:?}: expected {:?}, got {:?}",
+            day,
+            input,
+            expected,
+            errors
+        );
+    }
+}
+
+#[test]
+fn test_overflow() {
+    let crabs = "0,4294967295,".repeat(5);
+    let chunks = "(".repeat(30) + "\n";
+    let cases: &[(u8, &str, &str)] = &[
+        (
+            2,
+            "forward 2147483647\nforward 1\n",
+            "part 1: overflow computing horizontal position",
+        ),
+        (
+            2,
+            "forward 65536\ndown 65536\n",
+            "part 1: overflow computing horizontal position * depth",
+        ),
+        (
+            2,
+            "down 65536\nforward 65536\n",
+            "part 2: overflow computing depth",
+        ),
+        (
+            7,
+            crabs.trim_end_matches(','),
+            "part 2: overflow computing total fuel",
+        ),
+        (10, &chunks, "part 2: overflow computing completion score"),
+    ];
+    for &(day, input, expected) in cases {
+        let errors = solve(day, input)
+            .unwrap_or_else(|e| panic!("day {} panicked on {:?}: {}", day, input, e));
+        assert!(
+            errors.iter().any(|e| e == expected),
+            "day {} {:?}: expected {:?}, got {:?}",
+            day,
+            input,
+            expected,
+            errors
+        );
+    }
+}
+
+#[test]
+fn test_large_numbers() {
+    // Used to overflow adding up three measurements.
+    assert_eq!(
+        aoc2021::day01::part2("4294967295\n4294967295\n4294967295\n0\n").unwrap(),
+        0
+    );
+    // Scores are wide enough for any numbers on the boards.
+    let board = (0..25)
+        .map(|i| (u32::MAX - i).to_string())
+        .collect::<Vec<_>>()
+        .chunks(5)
+        .map(|row| row.join(" "))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let input = format!(
+        "4294967295,4294967294,4294967293,4294967292,4294967291\n\n{}\n",
+        board
+    );
+    assert_eq!(
+        aoc2021::day04::part1(&input).unwrap(),
+        "368934879713254442510"
+    );
+    // Reports are as wide as they need to be.
+    let wide = format!("{}\n{}\n", "1".repeat(200), "10".repeat(100));
+    assert_eq!(aoc2021::day03::part1(&wide).unwrap(), "0");
+    // The oxygen generator rating is the first line and the CO2 scrubber rating the second.
+    assert_eq!(aoc2021::day03::part2(&wide).unwrap(), "1721499918724605726437279448002007916219803861886149008553770761776372736024193407136973780636621858019943737591384593750");
+    // Used to try every position up to the largest one.
+    assert_eq!(
+        aoc2021::day07::part1("0,4294967295\n").unwrap(),
+        4_294_967_295
+    );
+}
+
+/// Changes the input a few times.
+fn mutate(rng: &mut Rng, input: &str) -> String {
+    let mut chars = input.chars().collect::<Vec<_>>();
+    let punctuation = [
+        '\n', ' ', ',', '-', '|', '>', '=', '(', ']', '#', '.', 'x', 'y', 'A',
+    ];
+    for _ in 0..rng.between(1, 4) {
+        if chars.is_empty() {
+            break;
+        }
+        let i = rng.below(chars.len());
+        match rng.below(6) {
+            0 => {
+                chars.remove(i);
+            }
+            1 => chars.insert(i, *rng.choose(&punctuation)),
+            2 => chars.truncate(i),
+            3 if chars[i].is_ascii_digit() => {
+                chars[i] = char::from(b'0' + rng.below(10) as u8);
+            }
+            4 => chars.splice(i..i, "4294967295".chars()).for_each(drop),
+            _ => {
+                let j = rng.below(chars.len());
+                chars.swap(i, j);
+            }
+        }
+    }
+    chars.into_iter().collect()
+}
+
+#[test]
+fn test_mutated_examples() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+    let mut failures = Vec::new();
+    for day in aoc2021::DAYS.iter().map(|d| d.day) {
+        let dir = root.join(format!("day{:02}", day));
+        for file in fs::read_dir(&dir).unwrap() {
+            let path = file.unwrap().path();
+            if path.extension().map_or(true, |e| e != "txt") {
+                continue;
+            }
+            let example = fs::read_to_string(&path).unwrap();
+            let mut rng = Rng::new(u64::from(day));
+            for _ in 0..50 {
+                let input = mutate(&mut rng, &example);
+                if let Err(e) = solve(day, &input) {
+                    failures.push(format!("day {} {:?}: {}", day, input, e));
+                }
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} mutated examples panicked:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}